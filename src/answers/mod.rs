@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{registry::Part, solution::Answer};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Unreadable {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Invalid(String),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Unreadable { path, error } => {
+                write!(f, "Could not read {}: {}", path.display(), error)
+            }
+            AnswersError::Invalid(message) => write!(f, "Invalid answers file: {}", message),
+        }
+    }
+}
+
+impl Error for AnswersError {}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unrecorded,
+}
+
+/// Known-correct answers, keyed by `[dayN]` tables with `part1`/`part2` values
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, AnswersError> {
+        let table = text
+            .parse::<toml::Value>()
+            .map_err(|e| AnswersError::Invalid(e.to_string()))?;
+        let mut answers = HashMap::new();
+        for (key, parts) in table.as_table().into_iter().flatten() {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|d| d.parse::<u8>().ok())
+                .ok_or_else(|| AnswersError::Invalid(format!("unknown table [{}]", key)))?;
+            for (part, expected) in parse_parts(key, parts)? {
+                answers.insert((day, part), expected);
+            }
+        }
+        Ok(Answers { answers })
+    }
+
+    /// Parses a single day's `part1`/`part2` values, as found in example fixtures
+    pub fn parse_day(day: u8, key: &str, parts: &toml::Value) -> Result<Answers, AnswersError> {
+        let answers = parse_parts(key, parts)?
+            .into_iter()
+            .map(|(part, expected)| ((day, part), expected))
+            .collect();
+        Ok(Answers { answers })
+    }
+
+    /// Reads `path`, or `answers.toml` from the working directory or the crate root
+    pub fn load(path: Option<&Path>) -> Result<Answers, AnswersError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => [
+                PathBuf::from(ANSWERS_FILE),
+                Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE),
+            ]
+            .into_iter()
+            .find(|p| p.exists())
+            .unwrap_or_else(|| PathBuf::from(ANSWERS_FILE)),
+        };
+        let text =
+            fs::read_to_string(&path).map_err(|error| AnswersError::Unreadable { path, error })?;
+        Answers::parse(&text)
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unrecorded,
+        }
+    }
+}
+
+fn parse_parts(key: &str, parts: &toml::Value) -> Result<Vec<(Part, String)>, AnswersError> {
+    let mut expected = Vec::new();
+    for (part_key, value) in parts.as_table().into_iter().flatten() {
+        let part = match part_key.as_str() {
+            "part1" => Part::One,
+            "part2" => Part::Two,
+            k => {
+                return Err(AnswersError::Invalid(format!(
+                    "unknown key {} in [{}]",
+                    k, key
+                )))
+            }
+        };
+        let answer = match value {
+            toml::Value::Integer(n) => n.to_string(),
+            toml::Value::String(s) => s.to_string(),
+            v => {
+                return Err(AnswersError::Invalid(format!(
+                    "{}.{} must be an integer or string, found {}",
+                    key,
+                    part_key,
+                    v.type_str()
+                )))
+            }
+        };
+        expected.push((part, answer));
+    }
+    Ok(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_STR: &str = r#"
+[day5]
+part1 = "CMZ"
+part2 = "MCD"
+
+[day10]
+part1 = 13140
+part2 = """
+##..
+..##"""
+"#;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(TEST_STR).unwrap();
+        assert_eq!(answers.get(5, Part::One), Some("CMZ"));
+        assert_eq!(answers.get(10, Part::One), Some("13140"));
+        assert_eq!(answers.get(10, Part::Two), Some("##..\n..##"));
+        assert_eq!(answers.get(1, Part::One), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Answers::parse("[days]\npart1 = 1").is_err());
+        assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+        assert!(Answers::parse("[day1]\npart1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(TEST_STR).unwrap();
+        assert_eq!(
+            answers.check(10, Part::One, &Answer::Integer(13140)),
+            Verdict::Correct
+        );
+        assert_eq!(
+            answers.check(
+                10,
+                Part::Two,
+                &Answer::Grid(vec!["##..".to_string(), "..##".to_string()])
+            ),
+            Verdict::Correct
+        );
+        assert_eq!(
+            answers.check(5, Part::Two, &Answer::from("CMZ")),
+            Verdict::Wrong {
+                expected: "MCD".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(1, Part::One, &Answer::Integer(1)),
+            Verdict::Unrecorded
+        );
+    }
+
+    #[test]
+    fn test_load_answers_file() {
+        let answers = Answers::load(None).unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("70764"));
+    }
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::{
+    registry::{Day, Part},
+    solution::{Answer, SolveError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        Stats {
+            mean: samples.iter().sum::<Duration>() / samples.len().max(1) as u32,
+            min: samples.iter().min().copied().unwrap_or_default(),
+            max: samples.iter().max().copied().unwrap_or_default(),
+        }
+    }
+
+    fn to_json(self) -> Value {
+        json!({
+            "mean_ns": self.mean.as_nanos() as u64,
+            "min_ns": self.min.as_nanos() as u64,
+            "max_ns": self.max.as_nanos() as u64,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    pub day: u8,
+    pub part: Part,
+    pub iterations: usize,
+    pub answer: Answer,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Benchmark {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "part": match self.part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            "iterations": self.iterations,
+            "answer": self.answer.to_string(),
+            "parse": self.parse.to_json(),
+            "solve": self.solve.to_json(),
+        })
+    }
+}
+
+/// Solves `part` of `day` `iterations` times, timing parse and solve separately
+pub fn bench(
+    day: &Day,
+    text: &str,
+    part: Part,
+    iterations: usize,
+) -> Result<Benchmark, SolveError> {
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+    let mut answer = None;
+    for _ in 0..iterations.max(1) {
+        let timed = (day.solve)(text, part)?;
+        parse_samples.push(timed.parse);
+        solve_samples.push(timed.solve);
+        answer = Some(timed.answer);
+    }
+
+    Ok(Benchmark {
+        day: day.day,
+        part,
+        iterations: iterations.max(1),
+        answer: answer.unwrap(),
+        parse: Stats::from_samples(&parse_samples),
+        solve: Stats::from_samples(&solve_samples),
+    })
+}
+
+pub fn report_json(benchmarks: &[Benchmark]) -> String {
+    let report = json!({
+        "benchmarks": benchmarks.iter().map(|b| b.to_json()).collect::<Vec<Value>>(),
+    });
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+pub fn report_table(benchmarks: &[Benchmark]) -> String {
+    let mut lines = vec![format!(
+        "{:>3} {:>4} {:>6}  {:>32}  {:>32}",
+        "Day", "Part", "Runs", "Parse mean / min / max", "Solve mean / min / max"
+    )];
+    for b in benchmarks {
+        lines.push(format!(
+            "{:>3} {:>4} {:>6}  {:>32}  {:>32}",
+            b.day,
+            b.part,
+            b.iterations,
+            format!(
+                "{:.2?} / {:.2?} / {:.2?}",
+                b.parse.mean, b.parse.min, b.parse.max
+            ),
+            format!(
+                "{:.2?} / {:.2?} / {:.2?}",
+                b.solve.mean, b.solve.min, b.solve.max
+            ),
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = Stats::from_samples(&[
+            Duration::from_millis(1),
+            Duration::from_millis(3),
+            Duration::from_millis(2),
+        ]);
+        assert_eq!(stats.mean, Duration::from_millis(2));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = registry::find(6).unwrap();
+        let benchmark = bench(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", Part::One, 3).unwrap();
+        assert_eq!(benchmark.iterations, 3);
+        assert_eq!(benchmark.answer, Answer::Integer(7));
+        assert!(benchmark.solve.min <= benchmark.solve.mean);
+        assert!(benchmark.solve.mean <= benchmark.solve.max);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let day = registry::find(6).unwrap();
+        let benchmark = bench(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", Part::Two, 2).unwrap();
+        let report: Value = serde_json::from_str(&report_json(&[benchmark])).unwrap();
+        let entry = &report["benchmarks"][0];
+        assert_eq!(entry["day"], 6);
+        assert_eq!(entry["part"], 2);
+        assert_eq!(entry["answer"], "19");
+        assert!(entry["solve"]["mean_ns"].is_u64());
+    }
+}
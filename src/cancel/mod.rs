@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+/// Returned by long-running solvers once their token has been cancelled or run out of time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled or out of time")
+    }
+}
+
+impl Error for Cancelled {}
+
+impl Cancelled {
+    /// Unwinds out of a solver, without the panic message, back to the nearest `run`
+    pub fn abort(self) -> ! {
+        panic::resume_unwind(Box::new(self))
+    }
+}
+
+/// Lets a long-running solver know when to give up, and where to draw its progress.
+/// Clones share the same cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+    progress: Option<MultiProgress>,
+}
+
+impl Token {
+    /// A token that is only cancelled by calling `cancel`
+    pub fn new() -> Token {
+        Token::default()
+    }
+
+    pub fn with_deadline(self, deadline: Instant) -> Token {
+        Token {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Token {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Draws progress bars from solvers using this token on `progress`
+    pub fn with_progress(self, progress: MultiProgress) -> Token {
+        Token {
+            progress: Some(progress),
+            ..self
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match self.is_cancelled() {
+            true => Err(Cancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// A progress bar counting up to `len`, hidden unless the token has somewhere to draw it
+    pub fn progress_bar(&self, len: u64, message: &str) -> ProgressBar {
+        let Some(progress) = &self.progress else {
+            return ProgressBar::hidden();
+        };
+        let bar = progress.add(ProgressBar::new(len));
+        bar.set_style(
+            ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} ({eta})")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        bar.set_message(message.to_string());
+        bar
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Token> = RefCell::new(Token::new());
+}
+
+/// The token for whatever is running on this thread, which never cancels outside of `run`
+pub fn current() -> Token {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs `f` with `token` as the current token, catching it if it aborts with `Cancelled`.
+/// Any other panic carries on unwinding.
+pub fn run<T>(token: &Token, f: impl FnOnce() -> T) -> Result<T, Cancelled> {
+    let previous = CURRENT.with(|current| current.replace(token.clone()));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CURRENT.with(|current| current.replace(previous));
+    match result {
+        Ok(value) => Ok(value),
+        Err(payload) => match payload.downcast::<Cancelled>() {
+            Ok(cancelled) => Err(*cancelled),
+            Err(payload) => panic::resume_unwind(payload),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let token = Token::new();
+        assert_eq!(token.check(), Ok(()));
+        token.clone().cancel();
+        assert_eq!(token.check(), Err(Cancelled));
+
+        assert!(Token::new().with_timeout(Duration::ZERO).is_cancelled());
+        assert!(!Token::new()
+            .with_timeout(Duration::from_secs(60))
+            .is_cancelled());
+    }
+
+    #[test]
+    fn test_run() {
+        let token = Token::new();
+        assert_eq!(run(&token, || current().is_cancelled()), Ok(false));
+        token.cancel();
+        assert_eq!(
+            run(&token, || current().check().unwrap_or_else(|c| c.abort())),
+            Err(Cancelled)
+        );
+        // Outside of `run` the current token never cancels
+        assert!(!current().is_cancelled());
+    }
+
+    #[test]
+    fn test_run_other_panics() {
+        let result = panic::catch_unwind(|| run(&Token::new(), || panic!("boom")));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,1147 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use indicatif::MultiProgress;
+
+use crate::{
+    answers::{Answers, Verdict},
+    bench::{self, Benchmark},
+    cancel::{self, Token},
+    client::{
+        self,
+        mock::{MockServer, Puzzles},
+        Client,
+    },
+    file::{self, InputSource},
+    generate::Rng,
+    registry::{self, Day, Part, DAYS},
+    report::{self, Format},
+    runner::{self, Outcome},
+    scaffold,
+    solution::Answer,
+    trace::{self, Filter},
+    visualize::{Ppm, Recorder, Terminal},
+};
+
+const USAGE: &str = "Usage:
+    aoc run --day <DAY> [--part <PART>] [--input <PATH|->] [--input-set <NAME>] [--timeout <SECS>] [--format <FORMAT>] [--trace <LEVELS>]
+    aoc run --all [--part <PART>] [--input-set <NAME>] [--timeout <SECS>] [--format <FORMAT>] [--trace <LEVELS>]
+    aoc compare --day <DAY> [--part <PART>] [--timeout <SECS>] [--format <FORMAT>] [--trace <LEVELS>]
+    aoc verify [--day <DAY>] [--answers <PATH>]
+    aoc bench [--day <DAY>] [--part <PART>] [--iterations <N>] [--json <PATH|->]
+    aoc visualize --day <DAY> [--part <PART>] [--input <PATH|->] [--delay <MS>]
+    aoc visualize --day <DAY> [--part <PART>] [--input <PATH|->] --ppm <DIR> [--scale <N>]
+    aoc generate --day <DAY> [--size <N>] [--seed <N>] [--output <PATH|->]
+    aoc new-day --day <DAY>
+    aoc fetch --day <DAY> [--output <PATH|->] [--server <URL>]
+    aoc submit --day <DAY> --part <PART> [--answer <ANSWER>] [--server <URL>]
+    aoc mock-server [--port <N>] [--session <TOKEN>]
+    aoc list
+    aoc help
+
+Inputs are read from --input (- for stdin), then $AOC_INPUT_DIR/dayN.txt,
+then src/dayN/input.txt. --input-set NAME reads inputs/NAME/dayN.txt instead,
+and compare runs a day against every input set and tabulates their answers.
+
+Each part gets --timeout seconds to finish, 60 by default with --all or
+compare and unlimited otherwise. --timeout 0 removes the limit. Only the
+long-running searches, days 15 and 16, check it as they go; other days always
+run to the end.
+
+--format is text (the default), json or csv. json and csv print one record
+per part with its day, part, input set, answer, answer type, duration in
+nanoseconds, status and any error.
+
+--trace sets how much solvers log to stderr: off (the default), info or
+trace, for every day or per day, as in --trace info,5=trace,16=off.
+
+visualize animates the simulation in the terminal, or with --ppm writes
+every frame to DIR as a numbered image.
+
+generate writes a random input for stress testing, the same every time for a
+given --seed, to --output (stdout by default).
+
+new-day creates src/dayN with a module skeleton and empty example and input
+files, and registers it so it runs like any other day.
+
+fetch downloads a day's input to src/dayN/input.txt, and submit sends an
+answer, solving the part first unless --answer is given. Both need $AOC_SESSION
+set to the adventofcode.com session cookie. Inputs and verdicts are cached in
+.aoc, so nothing is downloaded or submitted twice, and requests are spaced
+out. mock-server serves the checked-in inputs and answers.toml on localhost
+for trying them out with --server.";
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Day(u8),
+}
+
+impl Selection {
+    pub fn days(&self) -> Vec<&'static Day> {
+        match self {
+            Selection::All => DAYS.iter().collect(),
+            Selection::Day(day) => registry::find(*day).into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub selection: Selection,
+    pub parts: Vec<Part>,
+    pub input: InputSource,
+    /// How long each part may run before it is cancelled
+    pub timeout: Option<Duration>,
+    pub format: Format,
+    pub trace: Filter,
+}
+
+/// The time each part gets with `run --all`, so one runaway solver can't hang the run
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq)]
+pub struct CompareOptions {
+    pub day: u8,
+    pub parts: Vec<Part>,
+    /// How long each part may run on each input before it is cancelled
+    pub timeout: Option<Duration>,
+    pub format: Format,
+    pub trace: Filter,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyOptions {
+    pub selection: Selection,
+    pub answers: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub selection: Selection,
+    pub parts: Vec<Part>,
+    pub iterations: usize,
+    /// Where to write the JSON report, `-` for stdout
+    pub json: Option<String>,
+}
+
+/// Where `visualize` sends its frames
+#[derive(Debug, PartialEq)]
+pub enum Renderer {
+    Terminal { delay: Duration },
+    Ppm { directory: PathBuf, scale: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VisualizeOptions {
+    pub day: u8,
+    pub part: Part,
+    pub input: InputSource,
+    pub renderer: Renderer,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateOptions {
+    pub day: u8,
+    pub size: usize,
+    pub seed: u64,
+    /// Where to write the input, `-` for stdout
+    pub output: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FetchOptions {
+    pub day: u8,
+    /// Where to write the input, `-` for stdout, or the day's input.txt by default
+    pub output: Option<String>,
+    pub server: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitOptions {
+    pub day: u8,
+    pub part: Part,
+    /// The answer to send, or `None` to solve the part for it
+    pub answer: Option<String>,
+    pub server: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MockOptions {
+    pub port: u16,
+    pub session: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Compare(CompareOptions),
+    Verify(VerifyOptions),
+    Bench(BenchOptions),
+    Visualize(VisualizeOptions),
+    Generate(GenerateOptions),
+    NewDay(u8),
+    Fetch(FetchOptions),
+    Submit(SubmitOptions),
+    MockServer(MockOptions),
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    MissingCommand,
+    UnknownCommand(String),
+    UnexpectedArgument(String),
+    MissingValue(String),
+    InvalidValue { flag: String, value: String },
+    MissingDay,
+    UnknownDay(u8),
+    InputWithAll,
+    NotVisualized(u8),
+    NotGenerated(u8),
+    AlreadyRegistered(u8),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingCommand => write!(f, "No command given\n\n{}", USAGE),
+            CliError::UnknownCommand(c) => write!(f, "Unknown command '{}'\n\n{}", c, USAGE),
+            CliError::UnexpectedArgument(a) => write!(f, "Unexpected argument '{}'", a),
+            CliError::MissingValue(flag) => write!(f, "Missing value for {}", flag),
+            CliError::InvalidValue { flag, value } => {
+                write!(f, "Invalid value '{}' for {}", value, flag)
+            }
+            CliError::MissingDay => write!(f, "Pass either --day <DAY> or --all"),
+            CliError::UnknownDay(day) => write!(
+                f,
+                "Day {} is not registered (available: {})",
+                day,
+                DAYS.iter()
+                    .map(|d| d.day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            CliError::InputWithAll => write!(f, "--input can only be used with a single --day"),
+            CliError::NotVisualized(day) => write!(
+                f,
+                "Day {} has no visualization (available: {})",
+                day,
+                DAYS.iter()
+                    .filter(|d| d.visualize.is_some())
+                    .map(|d| d.day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            CliError::AlreadyRegistered(day) => write!(f, "Day {} is already registered", day),
+            CliError::NotGenerated(day) => write!(
+                f,
+                "Day {} has no input generator (available: {})",
+                day,
+                DAYS.iter()
+                    .filter(|d| d.generate.is_some())
+                    .map(|d| d.day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+fn parse_value<T: FromStr>(flag: &str, args: &mut std::slice::Iter<String>) -> Result<T, CliError> {
+    let value = args
+        .next()
+        .ok_or_else(|| CliError::MissingValue(flag.to_string()))?;
+    value.parse::<T>().map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_day(flag: &str, args: &mut std::slice::Iter<String>) -> Result<u8, CliError> {
+    let day: u8 = parse_value(flag, args)?;
+    match registry::find(day) {
+        Some(_) => Ok(day),
+        None => Err(CliError::UnknownDay(day)),
+    }
+}
+
+fn parse_part(flag: &str, args: &mut std::slice::Iter<String>) -> Result<Part, CliError> {
+    let part: u8 = parse_value(flag, args)?;
+    Part::try_from(part).map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: part.to_string(),
+    })
+}
+
+fn parse_run(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut selection = None;
+    let mut parts = Part::ALL.to_vec();
+    let mut input = InputSource::Search;
+    let mut timeout = None;
+    let mut format = Format::Text;
+    let mut trace = Filter::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => selection = Some(Selection::All),
+            "--format" => format = parse_value(arg, args)?,
+            "--trace" => trace = parse_value(arg, args)?,
+            "--timeout" => timeout = Some(parse_value::<u64>(arg, args)?),
+            "--day" => selection = Some(Selection::Day(parse_day(arg, args)?)),
+            "--part" => parts = vec![parse_part(arg, args)?],
+            "--input" => input = InputSource::from(parse_value::<String>(arg, args)?.as_str()),
+            "--input-set" => input = InputSource::Named(parse_value(arg, args)?),
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+
+    let selection = selection.ok_or(CliError::MissingDay)?;
+    // Every day has its own file in an input set, but not in a single --input
+    if selection == Selection::All && matches!(input, InputSource::Path(_) | InputSource::Stdin) {
+        return Err(CliError::InputWithAll);
+    }
+    let timeout = timeout_for(timeout, selection == Selection::All);
+    Ok(Command::Run(RunOptions {
+        selection,
+        parts,
+        input,
+        timeout,
+        format,
+        trace,
+    }))
+}
+
+/// `--timeout` in seconds, where 0 is no limit, defaulting to `DEFAULT_TIMEOUT` for runs of
+/// `many` parts
+fn timeout_for(seconds: Option<u64>, many: bool) -> Option<Duration> {
+    match (seconds, many) {
+        (Some(0), _) => None,
+        (Some(seconds), _) => Some(Duration::from_secs(seconds)),
+        (None, true) => Some(DEFAULT_TIMEOUT),
+        (None, false) => None,
+    }
+}
+
+fn parse_compare(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut parts = Part::ALL.to_vec();
+    let mut timeout = None;
+    let mut format = Format::Text;
+    let mut trace = Filter::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(arg, args)?),
+            "--part" => parts = vec![parse_part(arg, args)?],
+            "--timeout" => timeout = Some(parse_value::<u64>(arg, args)?),
+            "--format" => format = parse_value(arg, args)?,
+            "--trace" => trace = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    Ok(Command::Compare(CompareOptions {
+        day: day.ok_or(CliError::MissingDay)?,
+        parts,
+        timeout: timeout_for(timeout, true),
+        format,
+        trace,
+    }))
+}
+
+fn parse_verify(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut selection = Selection::All;
+    let mut answers = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => selection = Selection::Day(parse_day(arg, args)?),
+            "--answers" => answers = Some(parse_value(arg, args)?),
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    Ok(Command::Verify(VerifyOptions { selection, answers }))
+}
+
+fn parse_bench(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut options = BenchOptions {
+        selection: Selection::All,
+        parts: Part::ALL.to_vec(),
+        iterations: 10,
+        json: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => options.selection = Selection::All,
+            "--day" => options.selection = Selection::Day(parse_day(arg, args)?),
+            "--part" => options.parts = vec![parse_part(arg, args)?],
+            "--iterations" => options.iterations = parse_value(arg, args)?,
+            "--json" => options.json = Some(parse_value(arg, args)?),
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    if options.iterations == 0 {
+        return Err(CliError::InvalidValue {
+            flag: "--iterations".to_string(),
+            value: "0".to_string(),
+        });
+    }
+    Ok(Command::Bench(options))
+}
+
+fn parse_visualize(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut part = Part::One;
+    let mut input = InputSource::Search;
+    let mut delay = Duration::from_millis(50);
+    let mut directory = None;
+    let mut scale = 4;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(arg, args)?),
+            "--part" => part = parse_part(arg, args)?,
+            "--input" => input = InputSource::from(parse_value::<String>(arg, args)?.as_str()),
+            "--delay" => delay = Duration::from_millis(parse_value(arg, args)?),
+            "--ppm" => directory = Some(parse_value(arg, args)?),
+            "--scale" => scale = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+
+    let day = day.ok_or(CliError::MissingDay)?;
+    if registry::find(day).is_some_and(|d| d.visualize.is_none()) {
+        return Err(CliError::NotVisualized(day));
+    }
+    if scale == 0 {
+        return Err(CliError::InvalidValue {
+            flag: "--scale".to_string(),
+            value: "0".to_string(),
+        });
+    }
+    let renderer = match directory {
+        Some(directory) => Renderer::Ppm { directory, scale },
+        None => Renderer::Terminal { delay },
+    };
+    Ok(Command::Visualize(VisualizeOptions {
+        day,
+        part,
+        input,
+        renderer,
+    }))
+}
+
+fn parse_generate(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut size = 100;
+    let mut seed = 0;
+    let mut output = "-".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(arg, args)?),
+            "--size" => size = parse_value(arg, args)?,
+            "--seed" => seed = parse_value(arg, args)?,
+            "--output" => output = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+
+    let day = day.ok_or(CliError::MissingDay)?;
+    if registry::find(day).is_some_and(|d| d.generate.is_none()) {
+        return Err(CliError::NotGenerated(day));
+    }
+    Ok(Command::Generate(GenerateOptions {
+        day,
+        size,
+        seed,
+        output,
+    }))
+}
+
+fn parse_new_day(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Not parse_day, since the day isn't registered yet
+            "--day" => day = Some(parse_value(arg, args)?),
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    let day: u8 = day.ok_or(CliError::MissingDay)?;
+    if registry::find(day).is_some() {
+        return Err(CliError::AlreadyRegistered(day));
+    }
+    Ok(Command::NewDay(day))
+}
+
+fn parse_fetch(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut output = None;
+    let mut server = client::BASE_URL.to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(arg, args)?),
+            "--output" => output = Some(parse_value(arg, args)?),
+            "--server" => server = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    Ok(Command::Fetch(FetchOptions {
+        day: day.ok_or(CliError::MissingDay)?,
+        output,
+        server,
+    }))
+}
+
+fn parse_submit(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut part = None;
+    let mut answer = None;
+    let mut server = client::BASE_URL.to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(arg, args)?),
+            "--part" => part = Some(parse_part(arg, args)?),
+            "--answer" => answer = Some(parse_value(arg, args)?),
+            "--server" => server = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    Ok(Command::Submit(SubmitOptions {
+        day: day.ok_or(CliError::MissingDay)?,
+        part: part.ok_or_else(|| CliError::MissingValue("--part".to_string()))?,
+        answer,
+        server,
+    }))
+}
+
+fn parse_mock_server(args: &mut std::slice::Iter<String>) -> Result<Command, CliError> {
+    let mut options = MockOptions {
+        port: 8022,
+        session: "mock".to_string(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => options.port = parse_value(arg, args)?,
+            "--session" => options.session = parse_value(arg, args)?,
+            a => return Err(CliError::UnexpectedArgument(a.to_string())),
+        }
+    }
+    Ok(Command::MockServer(options))
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let mut args = args.iter();
+    let command = match args.next().map(|a| a.as_str()) {
+        Some("run") => parse_run(&mut args)?,
+        Some("compare") => parse_compare(&mut args)?,
+        Some("verify") => parse_verify(&mut args)?,
+        Some("bench") => parse_bench(&mut args)?,
+        Some("visualize") => parse_visualize(&mut args)?,
+        Some("generate") => parse_generate(&mut args)?,
+        Some("new-day") => parse_new_day(&mut args)?,
+        Some("fetch") => parse_fetch(&mut args)?,
+        Some("submit") => parse_submit(&mut args)?,
+        Some("mock-server") => parse_mock_server(&mut args)?,
+        Some("list") => Command::List,
+        Some("help" | "--help" | "-h") => Command::Help,
+        Some(c) => return Err(CliError::UnknownCommand(c.to_string())),
+        None => return Err(CliError::MissingCommand),
+    };
+    if let Some(a) = args.next() {
+        return Err(CliError::UnexpectedArgument(a.to_string()));
+    }
+    Ok(command)
+}
+
+/// Runs `jobs` with a token that draws progress bars, then prints every outcome in `format`
+/// once they have all finished, failing if any part did
+fn report_outcomes(
+    format: Format,
+    jobs: impl FnOnce(&Token) -> Vec<Outcome>,
+) -> Result<(), Box<dyn Error>> {
+    let token = Token::new().with_progress(MultiProgress::new());
+    let outcomes = jobs(&token);
+
+    println!("{}", report::render(&outcomes, format));
+    let failed = outcomes.iter().filter(|o| !o.status.is_ok()).count();
+    if failed > 0 {
+        return Err(format!("{} part(s) failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Runs every selected day in parallel, printing a summary once they have all finished
+fn run_all(options: RunOptions) -> Result<(), Box<dyn Error>> {
+    let days = options.selection.days();
+    report_outcomes(options.format, |token| {
+        runner::run_parallel(
+            &days,
+            &options.parts,
+            &options.input,
+            token,
+            options.timeout,
+        )
+    })
+}
+
+fn run_days(options: RunOptions) -> Result<(), Box<dyn Error>> {
+    trace::set_filter(options.trace.clone());
+    // Machine-readable output needs every outcome, including failures, in one report
+    if options.selection == Selection::All || options.format != Format::Text {
+        return run_all(options);
+    }
+    for day in options.selection.days() {
+        let text = day.input(&options.input)?;
+        for part in options.parts.iter() {
+            let mut token = Token::new().with_progress(MultiProgress::new());
+            if let Some(timeout) = options.timeout {
+                token = token.with_timeout(timeout);
+            }
+            println!("Day {} part {}", day.day, part);
+            let solve = || trace::for_day(day.day, || (day.solve)(&text, *part));
+            let timed = cancel::run(&token, solve)
+                .map_err(|_| format!("Day {} part {} timed out", day.day, part))??;
+            println!("{}", timed.answer);
+        }
+    }
+    Ok(())
+}
+
+/// Runs one day against every input set, printing a table once they have all finished
+fn compare_inputs(options: CompareOptions) -> Result<(), Box<dyn Error>> {
+    trace::set_filter(options.trace.clone());
+    let day = registry::find(options.day).unwrap();
+    let sets = file::input_sets(&day.module());
+    if sets.is_empty() {
+        return Err(format!(
+            "No input sets found for day {} (expected {}/<name>/{}.txt)",
+            day.day,
+            file::INPUT_SETS_DIR,
+            day.module()
+        )
+        .into());
+    }
+    report_outcomes(options.format, |token| {
+        runner::run_input_sets(day, &options.parts, &sets, token, options.timeout)
+    })
+}
+
+/// Checks every recorded answer, skipping parts with nothing recorded
+fn verify_days(options: VerifyOptions) -> Result<(), Box<dyn Error>> {
+    let answers = Answers::load(options.answers.as_deref())?;
+    let mut wrong = 0;
+    for day in options.selection.days() {
+        let mut text = None;
+        for part in Part::ALL {
+            if answers.get(day.day, part).is_none() {
+                println!("Day {} part {}: unrecorded", day.day, part);
+                continue;
+            }
+            if text.is_none() {
+                text = Some(day.input(&InputSource::Search)?);
+            }
+            let answer = (day.solve)(text.as_ref().unwrap(), part)?.answer;
+            match answers.check(day.day, part, &answer) {
+                Verdict::Correct => println!("Day {} part {}: ok", day.day, part),
+                Verdict::Wrong { expected } => {
+                    wrong += 1;
+                    println!(
+                        "Day {} part {}: WRONG\n  expected: {}\n  got:      {}",
+                        day.day, part, expected, answer
+                    );
+                }
+                Verdict::Unrecorded => unreachable!(),
+            }
+        }
+    }
+    if wrong > 0 {
+        return Err(format!("{} answer(s) did not match", wrong).into());
+    }
+    Ok(())
+}
+
+fn bench_days(options: BenchOptions) -> Result<(), Box<dyn Error>> {
+    let mut benchmarks: Vec<Benchmark> = Vec::new();
+    for day in options.selection.days() {
+        let text = day.input(&InputSource::Search)?;
+        for part in options.parts.iter() {
+            eprintln!("Benchmarking day {} part {}", day.day, part);
+            match panic::catch_unwind(|| bench::bench(day, &text, *part, options.iterations)) {
+                Ok(benchmark) => benchmarks.push(benchmark?),
+                Err(_) => eprintln!("Day {} part {} panicked, skipping", day.day, part),
+            }
+        }
+    }
+
+    match options.json.as_deref() {
+        Some("-") => println!("{}", bench::report_json(&benchmarks)),
+        Some(path) => {
+            fs::write(path, bench::report_json(&benchmarks))?;
+            println!("{}", bench::report_table(&benchmarks));
+        }
+        None => println!("{}", bench::report_table(&benchmarks)),
+    }
+    Ok(())
+}
+
+fn visualize_day(options: VisualizeOptions) -> Result<(), Box<dyn Error>> {
+    let day = registry::find(options.day).unwrap();
+    let visualize = day.visualize.ok_or(CliError::NotVisualized(day.day))?;
+    let text = day.input(&options.input)?;
+    let mut recorder: Box<dyn Recorder> = match options.renderer {
+        Renderer::Terminal { delay } => Box::new(Terminal::new(io::stdout(), delay)),
+        Renderer::Ppm { directory, scale } => Box::new(Ppm::new(directory, scale)),
+    };
+    visualize(&text, options.part, recorder.as_mut())?;
+    recorder.finish()?;
+    Ok(())
+}
+
+fn generate_input(options: GenerateOptions) -> Result<(), Box<dyn Error>> {
+    let day = registry::find(options.day).unwrap();
+    let generate = day.generate.ok_or(CliError::NotGenerated(day.day))?;
+    let text = generate(options.size, &mut Rng::new(options.seed));
+    match options.output.as_str() {
+        "-" => println!("{}", text),
+        path => fs::write(path, text + "\n")?,
+    }
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for path in scaffold::new_day(root, day)? {
+        println!(
+            "Wrote {}",
+            path.strip_prefix(root).unwrap_or(&path).display()
+        );
+    }
+    Ok(())
+}
+
+fn fetch_input(options: FetchOptions) -> Result<(), Box<dyn Error>> {
+    let client = Client::from_env(&options.server)?;
+    let text = client.fetch_input(options.day)?;
+    let day = registry::find(options.day).unwrap();
+    let output = options.output.unwrap_or_else(|| {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(day.module())
+            .join("input.txt")
+            .display()
+            .to_string()
+    });
+    match output.as_str() {
+        "-" => print!("{}", text),
+        path => {
+            fs::write(path, &text)?;
+            println!("Wrote {}", path);
+        }
+    }
+    Ok(())
+}
+
+fn submit_answer(options: SubmitOptions) -> Result<(), Box<dyn Error>> {
+    let client = Client::from_env(&options.server)?;
+    let answer = match options.answer {
+        Some(answer) => answer,
+        None => {
+            let day = registry::find(options.day).unwrap();
+            let text = day.input(&InputSource::Search)?;
+            match (day.solve)(&text, options.part)?.answer {
+                Answer::Grid(_) => {
+                    return Err(format!(
+                        "Day {} part {} draws its answer; read it and pass it with --answer",
+                        options.day, options.part
+                    )
+                    .into())
+                }
+                answer => answer.to_string(),
+            }
+        }
+    };
+    let verdict = client.submit(options.day, options.part, &answer)?;
+    println!(
+        "Day {} part {}: {} is {}",
+        options.day, options.part, answer, verdict
+    );
+    Ok(())
+}
+
+/// Serves every checked-in input and recorded answer until the process is stopped
+fn mock_server(options: MockOptions) -> Result<(), Box<dyn Error>> {
+    let answers = Answers::load(None)?;
+    let mut puzzles = Puzzles::new();
+    for day in DAYS {
+        if let Ok(text) = day.input(&InputSource::Search) {
+            puzzles = puzzles.with_input(day.day, &text);
+        }
+        for part in Part::ALL {
+            if let Some(answer) = answers.get(day.day, part) {
+                puzzles = puzzles.with_answer(day.day, part, answer);
+            }
+        }
+    }
+    let server = MockServer::start(options.port, &options.session, puzzles)?;
+    println!("Serving on {}", server.url());
+    println!(
+        "Try: {}={} aoc fetch --day 1 --server {}",
+        client::SESSION_VAR,
+        options.session,
+        server.url()
+    );
+    server.wait();
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match parse_args(args)? {
+        Command::Run(options) => run_days(options)?,
+        Command::Compare(options) => compare_inputs(options)?,
+        Command::Verify(options) => verify_days(options)?,
+        Command::Bench(options) => bench_days(options)?,
+        Command::Visualize(options) => visualize_day(options)?,
+        Command::Generate(options) => generate_input(options)?,
+        Command::NewDay(day) => new_day(day)?,
+        Command::Fetch(options) => fetch_input(options)?,
+        Command::Submit(options) => submit_answer(options)?,
+        Command::MockServer(options) => mock_server(options)?,
+        Command::List => {
+            for day in DAYS {
+                println!("{}", day.day);
+            }
+        }
+        Command::Help => println!("{}", USAGE),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_run_day() {
+        assert_eq!(
+            parse_args(&args("run --day 12 --part 2")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::Day(12),
+                parts: vec![Part::Two],
+                input: InputSource::Search,
+                timeout: None,
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --day 1")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::Day(1),
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Search,
+                timeout: None,
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_run_all() {
+        assert_eq!(
+            parse_args(&args("run --all")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::All,
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Search,
+                timeout: Some(DEFAULT_TIMEOUT),
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --all --timeout 0")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::All,
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Search,
+                timeout: None,
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --day 16 --timeout 5")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::Day(16),
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Search,
+                timeout: Some(Duration::from_secs(5)),
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_run_input() {
+        assert_eq!(
+            parse_args(&args("run --day 5 --input -")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::Day(5),
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Stdin,
+                timeout: None,
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --all --input day5.txt")),
+            Err(CliError::InputWithAll)
+        );
+    }
+
+    #[test]
+    fn test_parse_run_input_set() {
+        assert_eq!(
+            parse_args(&args("run --all --input-set alice")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::All,
+                parts: Part::ALL.to_vec(),
+                input: InputSource::Named("alice".to_string()),
+                timeout: Some(DEFAULT_TIMEOUT),
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_run_format() {
+        assert_eq!(
+            parse_args(&args("run --day 5 --format json --trace trace")),
+            Ok(Command::Run(RunOptions {
+                selection: Selection::Day(5),
+                parts: Part::ALL.to_vec(),
+                input: InputSource::Search,
+                timeout: None,
+                format: Format::Json,
+                trace: Filter::all(trace::Level::Trace),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --all --format xml")),
+            Err(CliError::InvalidValue {
+                flag: "--format".to_string(),
+                value: "xml".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        assert_eq!(
+            parse_args(&args("compare --day 3 --part 2")),
+            Ok(Command::Compare(CompareOptions {
+                day: 3,
+                parts: vec![Part::Two],
+                timeout: Some(DEFAULT_TIMEOUT),
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("compare --day 3 --timeout 0")),
+            Ok(Command::Compare(CompareOptions {
+                day: 3,
+                parts: Part::ALL.to_vec(),
+                timeout: None,
+                format: Format::Text,
+                trace: Filter::default(),
+            }))
+        );
+        assert_eq!(parse_args(&args("compare")), Err(CliError::MissingDay));
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(
+            parse_args(&args("verify")),
+            Ok(Command::Verify(VerifyOptions {
+                selection: Selection::All,
+                answers: None,
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("verify --day 3 --answers mine.toml")),
+            Ok(Command::Verify(VerifyOptions {
+                selection: Selection::Day(3),
+                answers: Some(PathBuf::from("mine.toml")),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse_args(&args("bench --day 15 --part 1 --iterations 3 --json -")),
+            Ok(Command::Bench(BenchOptions {
+                selection: Selection::Day(15),
+                parts: vec![Part::One],
+                iterations: 3,
+                json: Some("-".to_string()),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("bench --iterations 0")),
+            Err(CliError::InvalidValue {
+                flag: "--iterations".to_string(),
+                value: "0".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_visualize() {
+        assert_eq!(
+            parse_args(&args("visualize --day 14 --part 2 --delay 10")),
+            Ok(Command::Visualize(VisualizeOptions {
+                day: 14,
+                part: Part::Two,
+                input: InputSource::Search,
+                renderer: Renderer::Terminal {
+                    delay: Duration::from_millis(10)
+                },
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("visualize --day 9 --ppm frames")),
+            Ok(Command::Visualize(VisualizeOptions {
+                day: 9,
+                part: Part::One,
+                input: InputSource::Search,
+                renderer: Renderer::Ppm {
+                    directory: PathBuf::from("frames"),
+                    scale: 4
+                },
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("visualize --day 1")),
+            Err(CliError::NotVisualized(1))
+        );
+        assert_eq!(parse_args(&args("visualize")), Err(CliError::MissingDay));
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        assert_eq!(
+            parse_args(&args("generate --day 12")),
+            Ok(Command::Generate(GenerateOptions {
+                day: 12,
+                size: 100,
+                seed: 0,
+                output: "-".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(
+                "generate --day 5 --size 1000 --seed 42 --output big.txt"
+            )),
+            Ok(Command::Generate(GenerateOptions {
+                day: 5,
+                size: 1000,
+                seed: 42,
+                output: "big.txt".to_string(),
+            }))
+        );
+        assert_eq!(parse_args(&args("generate")), Err(CliError::MissingDay));
+    }
+
+    #[test]
+    fn test_parse_new_day() {
+        assert_eq!(
+            parse_args(&args("new-day --day 17")),
+            Ok(Command::NewDay(17))
+        );
+        assert_eq!(
+            parse_args(&args("new-day --day 1")),
+            Err(CliError::AlreadyRegistered(1))
+        );
+        assert_eq!(parse_args(&args("new-day")), Err(CliError::MissingDay));
+    }
+
+    #[test]
+    fn test_parse_client() {
+        assert_eq!(
+            parse_args(&args("fetch --day 3 --output -")),
+            Ok(Command::Fetch(FetchOptions {
+                day: 3,
+                output: Some("-".to_string()),
+                server: client::BASE_URL.to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(
+                "submit --day 3 --part 2 --server http://localhost:8022"
+            )),
+            Ok(Command::Submit(SubmitOptions {
+                day: 3,
+                part: Part::Two,
+                answer: None,
+                server: "http://localhost:8022".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("submit --day 3")),
+            Err(CliError::MissingValue("--part".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("mock-server --port 0")),
+            Ok(Command::MockServer(MockOptions {
+                port: 0,
+                session: "mock".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_args(&args("")), Err(CliError::MissingCommand));
+        assert_eq!(
+            parse_args(&args("walk")),
+            Err(CliError::UnknownCommand("walk".to_string()))
+        );
+        assert_eq!(parse_args(&args("run")), Err(CliError::MissingDay));
+        assert_eq!(
+            parse_args(&args("run --day 17")),
+            Err(CliError::UnknownDay(17))
+        );
+        assert_eq!(
+            parse_args(&args("run --day")),
+            Err(CliError::MissingValue("--day".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("run --day 1 --part 3")),
+            Err(CliError::InvalidValue {
+                flag: "--part".to_string(),
+                value: "3".to_string()
+            })
+        );
+        assert_eq!(
+            parse_args(&args("list extra")),
+            Err(CliError::UnexpectedArgument("extra".to_string()))
+        );
+    }
+}
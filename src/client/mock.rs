@@ -0,0 +1,315 @@
+//! A stand-in for adventofcode.com, serving known inputs and judging answers, so the client
+//! can be exercised without a network or a real session.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::registry::Part;
+
+use super::YEAR;
+
+/// What the mock knows about the puzzles, and what has happened so far
+#[derive(Debug, Default)]
+pub struct Puzzles {
+    pub inputs: HashMap<u8, String>,
+    pub answers: HashMap<(u8, Part), String>,
+    /// How long a wrong answer locks out further submissions
+    pub cooldown: Duration,
+    solved: HashSet<(u8, Part)>,
+    locked_until: Option<Instant>,
+    requests: usize,
+}
+
+impl Puzzles {
+    pub fn new() -> Puzzles {
+        Puzzles::default()
+    }
+
+    pub fn with_input(mut self, day: u8, input: &str) -> Puzzles {
+        self.inputs.insert(day, input.to_string());
+        self
+    }
+
+    pub fn with_answer(mut self, day: u8, part: Part, answer: &str) -> Puzzles {
+        self.answers.insert((day, part), answer.to_string());
+        self
+    }
+
+    pub fn with_cooldown(self, cooldown: Duration) -> Puzzles {
+        Puzzles { cooldown, ..self }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    cookie: Option<String>,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let method = words.next().unwrap_or_default().to_string();
+    let path = words.next().unwrap_or_default().to_string();
+
+    let mut cookie = None;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "cookie" => cookie = Some(value.trim().to_string()),
+            "content-length" => length = value.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        cookie,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+/// Decodes one `application/x-www-form-urlencoded` value
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn form_value(body: &str, key: &str) -> Option<String> {
+    body.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| decode(v))
+}
+
+/// An article in the shape adventofcode.com wraps its messages in
+fn page(message: &str) -> String {
+    format!(
+        "<html><body><main><article><p>{}</p></article></main></body></html>",
+        message
+    )
+}
+
+/// The day in a `/<year>/day/<day>/<rest>` path, and the rest
+fn route(path: &str) -> Option<(u8, &str)> {
+    let rest = path.strip_prefix(&format!("/{}/day/", YEAR))?;
+    let (day, rest) = rest.split_once('/')?;
+    Some((day.parse().ok()?, rest))
+}
+
+fn judge(puzzles: &mut Puzzles, day: u8, body: &str) -> (u16, String) {
+    let part = form_value(body, "level").and_then(|l| Part::try_from(l.parse::<u8>().ok()?).ok());
+    let (Some(part), Some(answer)) = (part, form_value(body, "answer")) else {
+        return (400, "Bad request".to_string());
+    };
+    let Some(expected) = puzzles.answers.get(&(day, part)) else {
+        return (404, "Not found".to_string());
+    };
+    if puzzles.solved.contains(&(day, part)) {
+        let message = "You don't seem to be solving the right level.  Did you already complete it?";
+        return (200, page(message));
+    }
+    if let Some(wait) = puzzles
+        .locked_until
+        .and_then(|until| until.checked_duration_since(Instant::now()))
+    {
+        let message = format!(
+            "You gave an answer too recently; you have to wait after submitting an answer before \
+             trying again.  You have {}s left to wait.",
+            wait.as_secs().max(1)
+        );
+        return (200, page(&message));
+    }
+
+    if answer.trim() == expected {
+        puzzles.solved.insert((day, part));
+        return (
+            200,
+            page("That's the right answer!  You are one gold star closer."),
+        );
+    }
+    puzzles.locked_until = Some(Instant::now() + puzzles.cooldown);
+    let hint = match (answer.trim().parse::<i64>(), expected.parse::<i64>()) {
+        (Ok(answer), Ok(expected)) if answer > expected => "  your answer is too high.",
+        (Ok(answer), Ok(expected)) if answer < expected => "  your answer is too low.",
+        _ => "",
+    };
+    (200, page(&format!("That's not the right answer;{}", hint)))
+}
+
+fn respond(puzzles: &Mutex<Puzzles>, session: &str, request: &Request) -> (u16, String) {
+    let mut puzzles = puzzles.lock().unwrap();
+    puzzles.requests += 1;
+    if request.cookie.as_deref() != Some(&format!("session={}", session)) {
+        return (
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.".to_string(),
+        );
+    }
+    match (request.method.as_str(), route(&request.path)) {
+        ("GET", Some((day, "input"))) => match puzzles.inputs.get(&day) {
+            Some(input) => (200, format!("{}\n", input)),
+            None => (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!".to_string(),
+            ),
+        },
+        ("POST", Some((day, "answer"))) => judge(&mut puzzles, day, &request.body),
+        _ => (404, "404 Not Found".to_string()),
+    }
+}
+
+fn handle(stream: TcpStream, puzzles: &Mutex<Puzzles>, session: &str) -> io::Result<()> {
+    let request = read_request(&stream)?;
+    let (status, body) = respond(puzzles, session, &request);
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        _ => "Not Found",
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// A mock server answering on a local port until it is dropped
+pub struct MockServer {
+    address: SocketAddr,
+    puzzles: Arc<Mutex<Puzzles>>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts serving `puzzles` on `port` of localhost, or any free port for 0, to requests
+    /// carrying `session`
+    pub fn start(port: u16, session: &str, puzzles: Puzzles) -> io::Result<MockServer> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let address = listener.local_addr()?;
+        let puzzles = Arc::new(Mutex::new(puzzles));
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let shared = Arc::clone(&puzzles);
+        let stop = Arc::clone(&stopping);
+        let session = session.to_string();
+        let thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                // A client hanging up early only loses its own response
+                if let Ok(stream) = stream {
+                    let _ = handle(stream, &shared, &session);
+                }
+            }
+        });
+        Ok(MockServer {
+            address,
+            puzzles,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+
+    /// The base URL to point a client at
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// How many requests have been made so far
+    pub fn requests(&self) -> usize {
+        self.puzzles.lock().unwrap().requests
+    }
+
+    /// Blocks until the server stops, which it only does when the process ends
+    pub fn wait(mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::Relaxed);
+        // Wake the listener so it notices
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("a+b%2Cc"), "a b,c");
+        assert_eq!(decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("/2022/day/7/input"), Some((7, "input")));
+        assert_eq!(route("/2021/day/7/input"), None);
+    }
+
+    #[test]
+    fn test_judge() {
+        let mut puzzles = Puzzles::new().with_answer(1, Part::One, "24000");
+        let (_, body) = judge(&mut puzzles, 1, "level=1&answer=100");
+        assert!(body.contains("too low"));
+        let (_, body) = judge(&mut puzzles, 1, "level=1&answer=24000");
+        assert!(body.contains("That's the right answer"));
+        let (_, body) = judge(&mut puzzles, 1, "level=1&answer=24000");
+        assert!(body.contains("Did you already complete it"));
+        assert_eq!(judge(&mut puzzles, 1, "level=2&answer=1").0, 404);
+    }
+}
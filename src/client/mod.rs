@@ -0,0 +1,416 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::registry::Part;
+
+pub mod mock;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2022;
+
+/// Environment variable holding the value of the adventofcode.com `session` cookie
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Where fetched inputs and submission records are kept, one directory per server
+pub const CACHE_DIR: &str = ".aoc";
+
+/// The least time between two requests to the server
+pub const DEFAULT_THROTTLE: Duration = Duration::from_secs(5);
+
+const SUBMISSIONS_FILE: &str = "submissions.jsonl";
+/// Touched on every request, so the throttle holds across separate runs
+const LAST_REQUEST_FILE: &str = "last-request";
+const USER_AGENT: &str = "github.com/sethsamuel/adventofcode-2022 aoc client";
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// The server could not be reached
+    Transport(String),
+    /// The server answered with an error, such as a bad session or a locked day
+    Status {
+        code: u16,
+        message: String,
+    },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => write!(
+                f,
+                "Set {} to your adventofcode.com session cookie",
+                SESSION_VAR
+            ),
+            ClientError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ClientError::Transport(message) => write!(f, "Request failed: {}", message),
+            ClientError::Status { code, message } => {
+                write!(f, "Server returned {}: {}", code, message.trim())
+            }
+        }
+    }
+}
+
+impl Error for ClientError {}
+
+/// What the server made of a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// With the server's hint, such as "too high", if it gave one
+    Wrong {
+        hint: Option<String>,
+    },
+    /// Submitted too soon after the last wrong answer, with how long is left to wait
+    TooSoon {
+        wait: Option<String>,
+    },
+    /// The part was solved before, so there is nothing to submit
+    AlreadySolved,
+    /// The server's response didn't look like any verdict
+    Unrecognised(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong { hint: Some(hint) } => write!(f, "wrong ({})", hint),
+            Verdict::Wrong { hint: None } => write!(f, "wrong"),
+            Verdict::TooSoon { wait: Some(wait) } => write!(f, "too soon, wait {}", wait),
+            Verdict::TooSoon { wait: None } => write!(f, "too soon"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unrecognised(_) => write!(f, "unrecognised response"),
+        }
+    }
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page returned for a submission
+    pub fn parse(page: &str) -> Verdict {
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            let hint = ["too high", "too low"]
+                .into_iter()
+                .find(|hint| page.contains(hint))
+                .map(|hint| hint.to_string());
+            Verdict::Wrong { hint }
+        } else if page.contains("You gave an answer too recently") {
+            let wait = page.split("You have ").nth(1).and_then(|rest| {
+                let wait = rest.split(" left to wait").next()?;
+                (wait.len() < rest.len()).then(|| wait.to_string())
+            });
+            Verdict::TooSoon { wait }
+        } else if page.contains("Did you already complete it") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unrecognised(page.to_string())
+        }
+    }
+
+    /// Whether the verdict is final, and worth remembering so the answer is never sent twice
+    fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Correct | Verdict::Wrong { .. } | Verdict::AlreadySolved
+        )
+    }
+}
+
+/// One answer sent to the server, as kept in the submissions file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Seconds since the Unix epoch
+    pub at: u64,
+}
+
+impl Submission {
+    fn to_json(&self) -> Value {
+        let (verdict, hint) = match &self.verdict {
+            Verdict::Correct => ("correct", None),
+            Verdict::Wrong { hint } => ("wrong", hint.as_deref()),
+            Verdict::AlreadySolved => ("already_solved", None),
+            Verdict::TooSoon { .. } | Verdict::Unrecognised(_) => ("unrecognised", None),
+        };
+        json!({
+            "day": self.day,
+            "part": self.part.to_string(),
+            "answer": self.answer,
+            "verdict": verdict,
+            "hint": hint,
+            "at": self.at,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Submission> {
+        let hint = value["hint"].as_str().map(|h| h.to_string());
+        Some(Submission {
+            day: value["day"].as_u64()? as u8,
+            part: value["part"]
+                .as_str()?
+                .parse::<u8>()
+                .ok()?
+                .try_into()
+                .ok()?,
+            answer: value["answer"].as_str()?.to_string(),
+            verdict: match value["verdict"].as_str()? {
+                "correct" => Verdict::Correct,
+                "wrong" => Verdict::Wrong { hint },
+                "already_solved" => Verdict::AlreadySolved,
+                _ => return None,
+            },
+            at: value["at"].as_u64()?,
+        })
+    }
+}
+
+/// Talks to adventofcode.com, or anything that looks like it, on behalf of one session
+pub struct Client {
+    base_url: String,
+    session: String,
+    cache_dir: PathBuf,
+    throttle: Duration,
+    agent: ureq::Agent,
+}
+
+/// A directory name for the server at `base_url`, so different servers never share a cache
+fn cache_name(base_url: &str) -> String {
+    let host = base_url.split("://").last().unwrap_or(base_url);
+    host.trim_end_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> ClientError + '_ {
+    move |error| ClientError::Io {
+        path: path.to_path_buf(),
+        error,
+    }
+}
+
+impl Client {
+    /// A client for the server at `base_url`, caching in this crate's `.aoc` directory
+    pub fn new(base_url: &str, session: &str) -> Client {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Client {
+            cache_dir: Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join(CACHE_DIR)
+                .join(cache_name(&base_url)),
+            base_url,
+            session: session.to_string(),
+            throttle: DEFAULT_THROTTLE,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// A client using the session from `AOC_SESSION`
+    pub fn from_env(base_url: &str) -> Result<Client, ClientError> {
+        match env::var(SESSION_VAR) {
+            Ok(session) if !session.trim().is_empty() => Ok(Client::new(base_url, session.trim())),
+            _ => Err(ClientError::MissingSession),
+        }
+    }
+
+    pub fn with_cache_dir(self, cache_dir: PathBuf) -> Client {
+        Client { cache_dir, ..self }
+    }
+
+    pub fn with_throttle(self, throttle: Duration) -> Client {
+        Client { throttle, ..self }
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Waits until the throttle allows another request, then marks the time of this one
+    fn wait_turn(&self) -> Result<(), ClientError> {
+        let marker = self.cache_dir.join(LAST_REQUEST_FILE);
+        let last = fs::metadata(&marker).and_then(|m| m.modified());
+        if let Some(elapsed) = last.ok().and_then(|last| last.elapsed().ok()) {
+            if elapsed < self.throttle {
+                thread::sleep(self.throttle - elapsed);
+            }
+        }
+        fs::create_dir_all(&self.cache_dir).map_err(io_error(&self.cache_dir))?;
+        fs::write(&marker, "").map_err(io_error(&marker))
+    }
+
+    fn day_url(&self, day: u8, path: &str) -> String {
+        format!("{}/{}/day/{}/{}", self.base_url, YEAR, day, path)
+    }
+
+    fn send(
+        &self,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String, ClientError> {
+        self.wait_turn()?;
+        let request = request.set("Cookie", &format!("session={}", self.session));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| ClientError::Transport(e.to_string())),
+            Err(ureq::Error::Status(code, response)) => Err(ClientError::Status {
+                code,
+                message: response.into_string().unwrap_or_default(),
+            }),
+            Err(e) => Err(ClientError::Transport(e.to_string())),
+        }
+    }
+
+    /// The day's puzzle input, downloaded only the first time it is asked for
+    pub fn fetch_input(&self, day: u8) -> Result<String, ClientError> {
+        let path = self.cache_dir.join(format!("day{}.txt", day));
+        if let Ok(text) = fs::read_to_string(&path) {
+            return Ok(text);
+        }
+        let text = self.send(self.agent.get(&self.day_url(day, "input")), None)?;
+        fs::write(&path, &text).map_err(io_error(&path))?;
+        Ok(text)
+    }
+
+    /// Every submission recorded so far, oldest first
+    pub fn submissions(&self) -> Result<Vec<Submission>, ClientError> {
+        let path = self.cache_dir.join(SUBMISSIONS_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(&path)(e)),
+        };
+        Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter_map(|value| Submission::from_json(&value))
+            .collect())
+    }
+
+    fn record(&self, submission: &Submission) -> Result<(), ClientError> {
+        let path = self.cache_dir.join(SUBMISSIONS_FILE);
+        fs::create_dir_all(&self.cache_dir).map_err(io_error(&self.cache_dir))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(io_error(&path))?;
+        writeln!(file, "{}", submission.to_json()).map_err(io_error(&path))
+    }
+
+    /// Submits an answer, unless the part is already solved or this answer was tried before,
+    /// in which case the recorded verdict is returned without asking the server again
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Verdict, ClientError> {
+        let answer = answer.trim();
+        let previous = self.submissions()?;
+        let mut previous = previous.iter().filter(|s| s.day == day && s.part == part);
+        if let Some(done) = previous.clone().find(|s| s.verdict == Verdict::Correct) {
+            return Ok(match done.answer == answer {
+                true => Verdict::Correct,
+                false => Verdict::AlreadySolved,
+            });
+        }
+        if let Some(tried) = previous.find(|s| s.answer == answer) {
+            return Ok(tried.verdict.clone());
+        }
+
+        let level = part.to_string();
+        let form = [("level", level.as_str()), ("answer", answer)];
+        let page = self.send(self.agent.post(&self.day_url(day, "answer")), Some(&form))?;
+        let verdict = Verdict::parse(&page);
+        if verdict.is_final() {
+            self.record(&Submission {
+                day,
+                part,
+                answer: answer.to_string(),
+                verdict: verdict.clone(),
+                at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+            })?;
+        }
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict_parse() {
+        assert_eq!(
+            Verdict::parse("<article><p>That's the right answer!</p></article>"),
+            Verdict::Correct
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer; your answer is too low."),
+            Verdict::Wrong {
+                hint: Some("too low".to_string())
+            }
+        );
+        assert_eq!(
+            Verdict::parse("You gave an answer too recently. You have 1m 12s left to wait."),
+            Verdict::TooSoon {
+                wait: Some("1m 12s".to_string())
+            }
+        );
+        assert!(matches!(
+            Verdict::parse("<html></html>"),
+            Verdict::Unrecognised(_)
+        ));
+    }
+
+    #[test]
+    fn test_submission_json() {
+        let submission = Submission {
+            day: 3,
+            part: Part::Two,
+            answer: "2602".to_string(),
+            verdict: Verdict::Wrong {
+                hint: Some("too high".to_string()),
+            },
+            at: 1670000000,
+        };
+        assert_eq!(
+            Submission::from_json(&submission.to_json()),
+            Some(submission)
+        );
+    }
+
+    #[test]
+    fn test_cache_name() {
+        assert_eq!(cache_name("https://adventofcode.com"), "adventofcode.com");
+        assert_eq!(cache_name("http://127.0.0.1:8080/"), "127.0.0.1_8080");
+    }
+}
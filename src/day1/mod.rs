@@ -1,81 +1,96 @@
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Debug, Clone)]
-struct Elf {
-    total_weight: u64,
+pub struct Elf {
+    pub total_weight: u64,
 }
 
-fn find_highest_weight(elfs: &mut Vec<Elf>) -> Elf {
+pub fn find_highest_weight(elfs: &mut [Elf]) -> Elf {
     elfs.sort_by_key(|e| e.total_weight);
     elfs.last().unwrap().to_owned()
 }
 
-fn find_top_3_weight(elfs: &mut Vec<Elf>) -> u64 {
+pub fn find_top_3_weight(elfs: &mut [Elf]) -> u64 {
     elfs.sort_by_key(|e| e.total_weight);
     elfs.iter().rev().take(3).map(|e| e.total_weight).sum()
 }
 
-fn parse_file(text: &str) -> Vec<Elf> {
-    let elf_lines = text.split("\n\n");
-    let elfs = elf_lines
-        .map(|lines| {
-            let weights: Vec<u64> = lines
-                .split("\n")
-                .map(|line| line.trim().parse::<u64>().unwrap())
-                .collect();
-            Elf {
-                total_weight: weights.into_iter().sum(),
-            }
-        })
-        .collect();
+pub fn parse_file(text: &str) -> Result<Vec<Elf>, ParseError> {
+    let mut elfs = vec![Elf { total_weight: 0 }];
+    for (i, line) in text.lines().enumerate() {
+        let weight = line.trim();
+        if weight.is_empty() {
+            elfs.push(Elf { total_weight: 0 });
+            continue;
+        }
+        let weight = weight.parse::<u64>().map_err(|e| {
+            ParseError::new(1, i, line, format!("invalid calories: {}", e)).at(weight)
+        })?;
+        elfs.last_mut().unwrap().total_weight += weight;
+    }
 
-    elfs
+    Ok(elfs)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A random list of `size` elves, each carrying a few snacks
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(3))
+        .map(|_| {
+            (0..rng.range(1..=10))
+                .map(|_| rng.range(1000..=60000).to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+pub struct Day1;
 
-    static TEST_STR: &str = "1000
-2000
-3000
+impl Solution for Day1 {
+    type Input<'a> = Vec<Elf>;
 
-4000
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(mut elfs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(find_highest_weight(&mut elfs).total_weight.into())
+    }
 
-5000
-6000
+    fn part2(mut elfs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(find_top_3_weight(&mut elfs).into())
+    }
+}
 
-7000
-8000
-9000
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-10000";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let elfs = parse_file(TEST_STR);
+        let elfs = parse_file(TEST_STR).unwrap();
         assert_eq!(elfs.len(), 5);
         assert_eq!(elfs.first().unwrap().total_weight, 1000 + 2000 + 3000);
     }
 
     #[test]
     fn test_find_highest_weight() {
-        let mut elfs = parse_file(TEST_STR);
+        let mut elfs = parse_file(TEST_STR).unwrap();
         let elf = find_highest_weight(&mut elfs);
         assert_eq!(elf.total_weight, 24_000);
     }
-}
 
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let elf = find_highest_weight(&mut parse_file(contents.as_str()));
-    println!("{}", elf.total_weight);
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-
-    println!("{}", find_top_3_weight(&mut parse_file(contents.as_str())));
+    #[test]
+    fn test_parse_error() {
+        let error = parse_file("1000\n\n20o0").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.text, "20o0");
+    }
 }
@@ -1,43 +1,76 @@
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    grid::Grid,
+    parse::ParseError,
+    registry::Part,
+    solution::{Answer, Solution, SolveError},
+    visualize::{Frame, Recorder, Visualize},
+};
 
 #[derive(Debug, PartialEq)]
-enum Command {
+pub enum Command {
     Add,
     Noop,
 }
 
-impl From<&str> for Command {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Command {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "noop" => Self::Noop,
-            "addx" => Self::Add,
-            _ => panic!("Unknown command {}", s),
+            "noop" => Ok(Self::Noop),
+            "addx" => Ok(Self::Add),
+            _ => Err(format!("unknown command '{}'", s)),
         }
     }
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     pub command: Command,
     pub argument: Option<i32>,
 }
 
-fn parse_file(text: &str) -> Vec<Instruction> {
-    let instructions: Vec<Instruction> = text
-        .lines()
-        .map(|l| {
-            let parts: Vec<&str> = l.split(" ").collect();
-            Instruction {
-                command: parts[0].into(),
-                argument: parts.get(1).unwrap_or(&"").parse::<i32>().ok(),
+/// How many cycles the screen has pixels for, six rows of 40
+pub const CYCLES: usize = 240;
+
+pub fn parse_file(text: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut cycles = 0;
+    text.lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let error = |message: String| ParseError::new(10, i, l, message);
+            let mut parts = l.split(' ');
+            let command = parts.next().unwrap_or_default();
+            let command: Command = command.try_into().map_err(|e| error(e).at(command))?;
+            let argument = match (&command, parts.next()) {
+                (Command::Noop, None) => None,
+                (Command::Add, Some(argument)) => Some(
+                    argument
+                        .parse::<i32>()
+                        .map_err(|_| error("invalid argument".to_string()).at(argument))?,
+                ),
+                (Command::Noop, Some(argument)) => {
+                    return Err(error("noop takes no argument".to_string()).at(argument))
+                }
+                (Command::Add, None) => return Err(error("addx needs an argument".to_string())),
+            };
+            cycles += match command {
+                Command::Add => 2,
+                Command::Noop => 1,
+            };
+            if cycles > CYCLES {
+                return Err(error(format!(
+                    "the program runs past the screen's {} cycles",
+                    CYCLES
+                )));
             }
+            Ok(Instruction { command, argument })
         })
-        .collect();
-
-    instructions
+        .collect()
 }
 
-fn execute(instructions: &Vec<Instruction>) -> Vec<i32> {
+pub fn execute(instructions: &[Instruction]) -> Vec<i32> {
     let mut cycle = 0;
     let mut register: i32 = 1;
 
@@ -68,33 +101,51 @@ fn execute(instructions: &Vec<Instruction>) -> Vec<i32> {
     signals
 }
 
-type Screen = Vec<Vec<bool>>;
+pub type Screen = Vec<Vec<bool>>;
 
-fn set_pixel(cycle: usize, register: i32, screen: &mut Screen) {
+pub fn set_pixel(cycle: usize, register: i32, screen: &mut Screen) {
     let line = (cycle - 1) / screen[0].len();
     let pixel = (cycle - 1) % screen[0].len();
 
     screen[line][pixel] = (register - pixel as i32).abs() <= 1;
 }
 
-fn draw(instructions: &Vec<Instruction>) -> Screen {
+pub fn draw(instructions: &[Instruction]) -> Screen {
+    draw_recorded(instructions, &mut ())
+}
+
+/// Draws lit pixels as `#`, dark ones as `.` and those not reached yet as spaces
+fn draw_screen(screen: &Screen, cycle: usize) -> Frame {
+    let mut frame = Grid::new(screen[0].len(), screen.len(), ' ');
+    for (i, pixel) in screen.iter().flatten().take(cycle).enumerate() {
+        frame[(i % screen[0].len(), i / screen[0].len())] = if *pixel { '#' } else { '.' };
+    }
+    frame
+}
+
+/// Runs the program, recording a frame of the screen after every cycle
+pub fn draw_recorded(instructions: &[Instruction], recorder: &mut dyn Recorder) -> Screen {
     let mut cycle: usize = 0;
     let mut register: i32 = 1;
 
     let mut screen: Screen = (0..6).map(|_| (0..40).map(|_| false).collect()).collect();
+    let mut tick = |cycle: &mut usize, register: i32, screen: &mut Screen| {
+        *cycle += 1;
+        set_pixel(*cycle, register, screen);
+        if recorder.is_recording() {
+            recorder.record(&draw_screen(screen, *cycle));
+        }
+    };
 
     for instr in instructions.iter() {
         match instr.command {
             Command::Add => {
-                cycle += 1;
-                set_pixel(cycle, register, &mut screen);
-                cycle += 1;
-                set_pixel(cycle, register, &mut screen);
+                tick(&mut cycle, register, &mut screen);
+                tick(&mut cycle, register, &mut screen);
                 register += instr.argument.unwrap_or(0);
             }
             Command::Noop => {
-                cycle += 1;
-                set_pixel(cycle, register, &mut screen);
+                tick(&mut cycle, register, &mut screen);
             }
         }
     }
@@ -102,167 +153,85 @@ fn draw(instructions: &Vec<Instruction>) -> Screen {
     screen
 }
 
+/// A random program running for `size` cycles, up to the `CYCLES` the screen has room for
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let cycles = size.clamp(1, CYCLES);
+    let mut cycle = 0;
+    let mut register = 1;
+    let mut lines = Vec::new();
+    while cycle < cycles {
+        if cycles - cycle >= 2 && !rng.one_in(3) {
+            // Keep the sprite somewhere on the screen
+            let target = rng.range_signed(0..=39);
+            lines.push(format!("addx {}", target - register));
+            register = target;
+            cycle += 2;
+        } else {
+            lines.push("noop".to_string());
+            cycle += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Vec<Instruction>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(instructions: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(execute(&instructions).iter().sum::<i32>().into())
+    }
+
+    fn part2(instructions: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(draw(&instructions)
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|p| if *p { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .into())
+    }
+}
+
+/// Both parts run the same program, so both watch the screen being drawn
+impl Visualize for Day10 {
+    fn visualize(instructions: Self::Input<'_>, _part: Part, recorder: &mut dyn Recorder) {
+        draw_recorded(&instructions, recorder);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::Frames;
 
-    static TEST_STR: &str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let instructions = parse_file(TEST_STR);
+        let instructions = parse_file(TEST_STR).unwrap();
         assert_eq!(instructions.len(), 146);
         assert_eq!(instructions[2].command, Command::Add);
     }
 
+    #[test]
+    fn test_parse_too_long() {
+        let program = vec!["addx 1"; CYCLES / 2].join("\n");
+        assert!(parse_file(&program).is_ok());
+        let error = parse_file(&(program + "\nnoop")).unwrap_err();
+        assert_eq!(error.line, CYCLES / 2 + 1);
+    }
+
     #[test]
     fn test_execute() {
-        let instructions = parse_file(TEST_STR);
+        let instructions = parse_file(TEST_STR).unwrap();
         let signals = execute(&instructions);
         assert_eq!(signals.len(), 6);
         assert_eq!(signals[0], 420);
@@ -271,7 +240,7 @@ noop";
 
     #[test]
     fn test_draw() {
-        let instructions = parse_file(TEST_STR);
+        let instructions = parse_file(TEST_STR).unwrap();
         let screen = draw(&instructions);
         for line in screen {
             println!(
@@ -282,29 +251,17 @@ noop";
             )
         }
     }
-}
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let instructions = parse_file(&contents);
-    let signals = execute(&instructions);
 
-    println!("{:?}", signals.iter().sum::<i32>());
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let instructions = parse_file(&contents);
-
-    let screen = draw(&instructions);
-    for line in screen {
-        println!(
-            "{}",
-            line.iter()
-                .map(|p| if *p { '#' } else { '.' })
-                .collect::<String>()
-        )
+    #[test]
+    fn test_draw_recorded_frames() {
+        let instructions = parse_file(TEST_STR).unwrap();
+        let mut frames = Frames::default();
+        draw_recorded(&instructions, &mut frames);
+        assert_eq!(frames.0.len(), 240);
+        assert_eq!(frames.0[2].row(0)[..4], ['#', '#', '.', ' ']);
+        assert_eq!(
+            frames.0[239].render(|c| *c),
+            Day10::part2(instructions).unwrap().to_string()
+        );
     }
 }
@@ -1,142 +1,165 @@
 use std::ops::{AddAssign, DivAssign, MulAssign, Rem};
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Debug, Default, Clone, PartialEq)]
-enum Operand {
+pub enum Operand {
     #[default]
     Mult,
     Add,
 }
 
-impl From<&str> for Operand {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Operand {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "*" => Self::Mult,
-            "+" => Self::Add,
-            _ => panic!("Unknown operand {}", s),
+            "*" => Ok(Self::Mult),
+            "+" => Ok(Self::Add),
+            _ => Err(format!("unknown operand '{}'", s)),
         }
     }
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
-enum Argument {
+pub enum Argument {
     Constant(usize),
     #[default]
     Old,
 }
 
-impl From<&str> for Argument {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Argument {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "old" => Self::Old,
-            i => Self::Constant(i.parse::<usize>().unwrap()),
+            "old" => Ok(Self::Old),
+            i => i
+                .parse::<usize>()
+                .map(Self::Constant)
+                .map_err(|_| format!("expected 'old' or a number, found '{}'", i)),
         }
     }
 }
 
 #[derive(Debug, Default, Clone)]
-struct Operation {
-    operand: Operand,
+pub struct Operation {
+    pub operand: Operand,
     // lhs: Argument,
-    rhs: Argument,
+    pub rhs: Argument,
 }
 
 #[derive(Debug, Default, Clone)]
-struct Monkey {
-    items: Vec<usize>,
-    divisor: usize,
-    true_target: usize,
-    false_target: usize,
-    operation: Operation,
-    inspections: usize,
+pub struct Monkey {
+    pub items: Vec<usize>,
+    pub divisor: usize,
+    pub true_target: usize,
+    pub false_target: usize,
+    pub operation: Operation,
+    pub inspections: usize,
 }
 
-fn parse_file(text: &str) -> Vec<Monkey> {
+pub fn parse_file(text: &str) -> Result<Vec<Monkey>, ParseError> {
     let mut monkeys = Vec::new();
     let lines = text.lines();
     let mut current_monkey = Monkey::default();
-    for line in lines {
+    for (i, line) in lines.enumerate() {
+        let error = |message: String| ParseError::new(11, i, line, message);
+        // The number at the end of the Test and If lines
+        let last_number = |value: &str| {
+            let n = value.rsplit(' ').next().unwrap_or_default();
+            n.parse::<usize>()
+                .map_err(|_| error("expected a number".to_string()).at(n))
+        };
         if line.is_empty() {
             monkeys.push(current_monkey);
             current_monkey = Monkey::default();
         } else if line.starts_with("Monkey") {
             //Noop
         } else {
-            let mut parts = line.split(":").map(|s| s.trim());
-            match parts.next() {
-                Some("Starting items") => {
-                    current_monkey.items = parts
-                        .next()
-                        .unwrap()
-                        .split(",")
-                        .map(|i| i.trim().parse::<usize>().unwrap())
-                        .collect()
+            let (key, value) = line
+                .split_once(':')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| error("expected 'key: value'".to_string()))?;
+            match key {
+                "Starting items" => {
+                    current_monkey.items = value
+                        .split(',')
+                        .map(|i| {
+                            let i = i.trim();
+                            i.parse::<usize>()
+                                .map_err(|_| error("invalid worry level".to_string()).at(i))
+                        })
+                        .collect::<Result<_, _>>()?
                 }
-                Some("Operation") => {
-                    let operation_parts: Vec<&str> = parts.next().unwrap().split(" ").collect();
+                "Operation" => {
+                    let operation_parts: Vec<&str> = value.split(' ').collect();
+                    if operation_parts.len() != 5 {
+                        return Err(error("expected 'new = old <op> <arg>'".to_string()).at(value));
+                    }
                     current_monkey.operation = Operation {
-                        operand: operation_parts[3].into(),
+                        operand: operation_parts[3]
+                            .try_into()
+                            .map_err(|e| error(e).at(operation_parts[3]))?,
                         // lhs: operation_parts[2].into(),
-                        rhs: operation_parts[4].into(),
+                        rhs: operation_parts[4]
+                            .try_into()
+                            .map_err(|e| error(e).at(operation_parts[4]))?,
                     }
                 }
-                Some("Test") => {
-                    current_monkey.divisor = parts
-                        .next()
-                        .unwrap()
-                        .split(" ")
-                        .last()
-                        .unwrap()
-                        .parse::<usize>()
-                        .unwrap()
-                }
-                Some("If true") => {
-                    current_monkey.true_target = parts
-                        .next()
-                        .unwrap()
-                        .split(" ")
-                        .last()
-                        .unwrap()
-                        .parse::<usize>()
-                        .unwrap()
-                }
-                Some("If false") => {
-                    current_monkey.false_target = parts
-                        .next()
-                        .unwrap()
-                        .split(" ")
-                        .last()
-                        .unwrap()
-                        .parse::<usize>()
-                        .unwrap()
+                "Test" => {
+                    current_monkey.divisor = last_number(value)?;
+                    if current_monkey.divisor == 0 {
+                        return Err(error("cannot divide by zero".to_string()).at(value));
+                    }
                 }
-                Some(s) => panic!("Unknown line {}", s),
-                None => (),
+                "If true" => current_monkey.true_target = last_number(value)?,
+                "If false" => current_monkey.false_target = last_number(value)?,
+                _ => return Err(error(format!("unknown line '{}'", key)).at(key)),
             }
         }
     }
     monkeys.push(current_monkey);
 
-    monkeys
+    // Monkey business multiplies the two busiest monkeys' inspections
+    if monkeys.len() < 2 {
+        return Err(ParseError::input(11, "need at least two monkeys"));
+    }
+    if let Some(m) = monkeys.iter().position(|m| m.divisor == 0) {
+        return Err(ParseError::input(11, format!("monkey {} has no test", m)));
+    }
+    if let Some(m) = monkeys
+        .iter()
+        .position(|m| m.true_target.max(m.false_target) >= monkeys.len())
+    {
+        return Err(ParseError::input(
+            11,
+            format!("monkey {} throws to a missing monkey", m),
+        ));
+    }
+    Ok(monkeys)
 }
 
-fn get_monkey_business(monkeys: &mut Vec<Monkey>) -> usize {
+pub fn get_monkey_business(monkeys: &mut [Monkey]) -> usize {
     for _ in 0..20 {
         for m in 0..monkeys.len() {
             let monkey = &mut monkeys[m];
             let mut items = Vec::new();
 
-            while monkey.items.len() > 0 {
+            while !monkey.items.is_empty() {
                 let mut item = monkey.items.remove(0);
                 match monkey.operation.operand {
                     Operand::Mult => item.mul_assign(match monkey.operation.rhs {
                         Argument::Constant(c) => c,
-                        Argument::Old => item.clone(),
+                        Argument::Old => item,
                     }),
                     Operand::Add => item.add_assign(match monkey.operation.rhs {
                         Argument::Constant(c) => c,
-                        Argument::Old => item.clone(),
+                        Argument::Old => item,
                     }),
                 }
                 item.div_assign(3);
@@ -158,23 +181,23 @@ fn get_monkey_business(monkeys: &mut Vec<Monkey>) -> usize {
     monkeys[0].inspections * monkeys[1].inspections
 }
 
-fn get_more_monkey_business(monkeys: &mut Vec<Monkey>) -> usize {
+pub fn get_more_monkey_business(monkeys: &mut [Monkey]) -> usize {
     let gcd: usize = monkeys.iter().map(|m| m.divisor).product();
     for _ in 0..10_000 {
         for m in 0..monkeys.len() {
             let monkey = &mut monkeys[m];
             let mut items = Vec::new();
 
-            while monkey.items.len() > 0 {
+            while !monkey.items.is_empty() {
                 let mut item = monkey.items.remove(0);
                 match monkey.operation.operand {
                     Operand::Mult => item.mul_assign(match monkey.operation.rhs {
                         Argument::Constant(c) => c,
-                        Argument::Old => item.clone(),
+                        Argument::Old => item,
                     }),
                     Operand::Add => item.add_assign(match monkey.operation.rhs {
                         Argument::Constant(c) => c,
-                        Argument::Old => item.clone(),
+                        Argument::Old => item,
                     }),
                 }
 
@@ -195,41 +218,74 @@ fn get_more_monkey_business(monkeys: &mut Vec<Monkey>) -> usize {
     monkeys[0].inspections * monkeys[1].inspections
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Eight random monkeys juggling `size` items between them. Worry levels are only ever
+/// multiplied by 2 or 3 so that dividing by 3 keeps them small in part 1.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let count = 8;
+    let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+    rng.shuffle(&mut divisors);
+    // Every monkey starts with at least one item
+    let mut items: Vec<Vec<usize>> = (0..count).map(|_| vec![rng.range(50..=99)]).collect();
+    for _ in count..size {
+        items[rng.below(count)].push(rng.range(50..=99));
+    }
+
+    (0..count)
+        .map(|m| {
+            let operation = match rng.below(3) {
+                0 => format!("* {}", rng.range(2..=3)),
+                _ => format!("+ {}", rng.range(1..=8)),
+            };
+            let if_true = (m + rng.range(1..=count - 1)) % count;
+            let if_false = (m + rng.range(1..=count - 1)) % count;
+            [
+                format!("Monkey {}:", m),
+                format!(
+                    "  Starting items: {}",
+                    items[m]
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                format!("  Operation: new = old {}", operation),
+                format!("  Test: divisible by {}", divisors[m]),
+                format!("    If true: throw to monkey {}", if_true),
+                format!("    If false: throw to monkey {}", if_false),
+            ]
+            .join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+pub struct Day11;
 
-    static TEST_STR: &str = "Monkey 0:
-Starting items: 79, 98
-Operation: new = old * 19
-Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
+impl Solution for Day11 {
+    type Input<'a> = Vec<Monkey>;
 
-Monkey 1:
-Starting items: 54, 65, 75, 74
-Operation: new = old + 6
-Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(mut monkeys: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_monkey_business(&mut monkeys).into())
+    }
+
+    fn part2(mut monkeys: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_more_monkey_business(&mut monkeys).into())
+    }
+}
 
-Monkey 2:
-Starting items: 79, 60, 97
-Operation: new = old * old
-Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-Monkey 3:
-Starting items: 74
-Operation: new = old + 3
-Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let monkeys = parse_file(TEST_STR);
+        let monkeys = parse_file(TEST_STR).unwrap();
         assert_eq!(monkeys.len(), 4);
         assert_eq!(monkeys[0].items.len(), 2);
         assert_eq!(monkeys[2].true_target, 1);
@@ -238,33 +294,32 @@ Test: divisible by 17
         assert_eq!(monkeys[3].operation.rhs, Argument::Constant(3));
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let first = TEST_STR.split("\n\n").next().unwrap();
+        let error = parse_file(first).unwrap_err();
+        assert_eq!(error.message, "need at least two monkeys");
+        let untested = TEST_STR.replacen("Test: divisible by 23\n", "", 1);
+        let error = parse_file(&untested).unwrap_err();
+        assert_eq!(error.message, "monkey 0 has no test");
+        let error = parse_file(&TEST_STR.replacen("by 23", "by 0", 1)).unwrap_err();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (4, "cannot divide by zero")
+        );
+    }
+
     #[test]
     fn test_get_monkey_business() {
-        let mut monkeys = parse_file(TEST_STR);
+        let mut monkeys = parse_file(TEST_STR).unwrap();
         let mb = get_monkey_business(&mut monkeys);
         assert_eq!(mb, 10605);
     }
 
     #[test]
     fn test_get_more_monkey_business() {
-        let mut monkeys = parse_file(TEST_STR);
+        let mut monkeys = parse_file(TEST_STR).unwrap();
         let mb = get_more_monkey_business(&mut monkeys);
         assert_eq!(mb, 2713310158);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let mut monkeys = parse_file(&contents);
-    let mb = get_monkey_business(&mut monkeys);
-    println!("{}", mb);
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let mut monkeys = parse_file(&contents);
-    let mb = get_more_monkey_business(&mut monkeys);
-    println!("{}", mb);
-}
@@ -1,148 +1,133 @@
-use std::vec;
-
-use crate::file::read_file;
-
-fn parse_file(text: &str) -> (Vec<Vec<u8>>, (usize, usize), (usize, usize)) {
-    let mut start: (usize, usize) = (0, 0);
-    let mut end: (usize, usize) = (0, 0);
-    let grid = text
-        .lines()
-        .enumerate()
-        .map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .map(|(x, c)| {
-                    if c == 'S' {
-                        start = (y, x);
-                        return 1;
-                    }
-                    if c == 'E' {
-                        end = (y, x);
-                        return 26;
-                    }
-                    c as u8 - 96
-                })
-                .collect()
-        })
-        .collect();
+use crate::{
+    generate::Rng,
+    grid::Grid,
+    parse::ParseError,
+    pathfinding::bfs,
+    solution::{Answer, Solution, SolveError},
+};
+
+pub type HeightMap = Grid<u8>;
+/// An `(x, y)` position on the height map
+pub type Coord = (usize, usize);
+
+pub fn parse_file(text: &str) -> Result<(HeightMap, Coord, Coord), ParseError> {
+    let chars = Grid::parse(12, text, |c| {
+        matches!(c, 'a'..='z' | 'S' | 'E').then_some(c)
+    })?;
+    let start = chars
+        .position(|c| *c == 'S')
+        .ok_or_else(|| ParseError::input(12, "no start position 'S'"))?;
+    let end = chars
+        .position(|c| *c == 'E')
+        .ok_or_else(|| ParseError::input(12, "no end position 'E'"))?;
+    let grid = chars.map(|c| match c {
+        'S' => 1,
+        'E' => 26,
+        c => *c as u8 - 96,
+    });
+
+    Ok((grid, start, end))
+}
 
-    (grid, start, end)
+/// Squares reachable in one step: up at most one level, or down any amount
+fn climbable(grid: &HeightMap, (x, y): Coord) -> impl Iterator<Item = Coord> + '_ {
+    let height = grid[(x, y)];
+    grid.neighbours4(x, y)
+        .filter(move |next| grid[*next] <= height + 1)
 }
 
-fn get_shortest_walk(grid: &Vec<Vec<u8>>, start: (usize, usize), end: (usize, usize)) -> usize {
-    let mut distances = (0..grid.len())
-        .map(|i| {
-            (0..grid[i].len())
-                .map(|_| usize::MAX)
-                .collect::<Vec<usize>>()
-        })
-        .collect::<Vec<Vec<usize>>>();
-
-    distances[start.0][start.1] = 0;
-
-    let mut queue: Vec<(usize, usize)> = vec![start];
-    while let Some(current) = queue.pop() {
-        let current_value = grid[current.0][current.1];
-        let current_distance = distances[current.0][current.1];
-
-        for vector in vec![(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let coords: (usize, usize) = (
-                (current.0 as isize + vector.0) as usize,
-                (current.1 as isize + vector.1) as usize,
-            );
-            if (0..grid.len()).contains(&(coords.0))
-                && (0..grid[current.0].len()).contains(&(coords.1))
-            {
-                let value = grid[coords.0][coords.1];
-                let distance = distances[coords.0][coords.1];
-                if value <= current_value + 1 && (distance > current_distance + 1) {
-                    distances[coords.0][coords.1] = current_distance + 1;
-                    queue.push((coords.0, coords.1));
-                }
-            }
-        }
-    }
+/// The shortest walk from `start` to `end`, if there is one
+pub fn get_shortest_walk(grid: &HeightMap, start: Coord, end: Coord) -> Option<usize> {
+    bfs([start], |current| climbable(grid, *current)).distance(&end)
+}
 
-    // for d in distances.clone() {
-    //     println!(
-    //         "{}",
-    //         d.iter()
-    //             .map(|d| match *d {
-    //                 usize::MAX => "X".to_string(),
-    //                 _ => format!(" {:0>2} ", d.to_string()),
-    //             })
-    //             .collect::<String>()
-    //     );
-    // }
-
-    distances[end.0][end.1]
+/// The shortest walk to `end` from any square at the lowest level, if any can reach it
+pub fn get_shortest_trailhead(grid: &HeightMap, end: Coord) -> Option<usize> {
+    let trailheads = grid.iter().filter(|(_, h)| **h == 1).map(|(c, _)| c);
+    bfs(trailheads, |current| climbable(grid, *current)).distance(&end)
 }
 
-fn get_shortest_trailhead(grid: &Vec<Vec<u8>>, end: (usize, usize)) -> usize {
-    let mut shortest_distance = usize::MAX;
-    for row in 0..grid.len() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 1 {
-                let distance = get_shortest_walk(grid, (row, col), end);
-                // println!("{:?} {}", (row, col), distance);
-                if distance < shortest_distance {
-                    // println!("{:?}", (row, col));
-                    shortest_distance = distance;
-                }
-            }
-        }
+/// A random heightmap `size` wide and half as tall. A trail climbing steadily along the top
+/// and down the right hand side always leads from `S` to `E`.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    // Wide enough for the trail to climb from a to z one step at a time
+    let (width, height) = (size.max(26), (size / 2).max(2));
+    let mut rows: Vec<Vec<char>> = (0..height)
+        .map(|_| (0..width).map(|_| rng.lowercase()).collect())
+        .collect();
+    let trail: Vec<(usize, usize)> = (0..width)
+        .map(|x| (x, 0))
+        .chain((1..height).map(|y| (width - 1, y)))
+        .collect();
+    for (i, (x, y)) in trail.iter().enumerate() {
+        rows[*y][*x] = (b'a' + (i * 25 / (trail.len() - 1)) as u8) as char;
+    }
+    rows[0][0] = 'S';
+    rows[height - 1][width - 1] = 'E';
+    rows.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input<'a> = (HeightMap, Coord, Coord);
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1((grid, start, end): Self::Input<'_>) -> Result<Answer, SolveError> {
+        get_shortest_walk(&grid, start, end)
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::unsolvable(12, "E can't be reached from S"))
+    }
+
+    fn part2((grid, _start, end): Self::Input<'_>) -> Result<Answer, SolveError> {
+        get_shortest_trailhead(&grid, end)
+            .map(Answer::from)
+            .ok_or_else(|| {
+                SolveError::unsolvable(12, "E can't be reached from any square at the lowest level")
+            })
     }
-    shortest_distance
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let (grid, start, end) = parse_file(TEST_STR);
+        let (grid, start, end) = parse_file(TEST_STR).unwrap();
         assert_eq!(start, (0, 0));
-        assert_eq!(end, (2, 5));
-        assert_eq!(grid[0][2], 2);
-        assert_eq!(grid[3][2], 3);
-        assert_eq!(grid[2][5], 26);
+        assert_eq!(end, (5, 2));
+        assert_eq!(grid[(2, 0)], 2);
+        assert_eq!(grid[(2, 3)], 3);
+        assert_eq!(grid[(5, 2)], 26);
     }
 
     #[test]
     fn test_get_shortest_walk() {
-        let (grid, start, end) = parse_file(TEST_STR);
-        assert_eq!(get_shortest_walk(&grid, start, end), 31);
+        let (grid, start, end) = parse_file(TEST_STR).unwrap();
+        assert_eq!(get_shortest_walk(&grid, start, end), Some(31));
     }
 
     #[test]
     fn test_get_shortest_trailhead() {
-        let (grid, _start, end) = parse_file(TEST_STR);
+        let (grid, _start, end) = parse_file(TEST_STR).unwrap();
 
-        assert_eq!(get_shortest_trailhead(&grid, end), 29);
+        assert_eq!(get_shortest_trailhead(&grid, end), Some(29));
     }
-}
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let (grid, start, end) = parse_file(&contents);
-    let walk = get_shortest_walk(&grid, start, end);
 
-    println!("{}", walk);
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let (grid, _start, end) = parse_file(&contents);
-    let start = get_shortest_trailhead(&grid, end);
-
-    println!("{}", start);
+    #[test]
+    fn test_unreachable() {
+        let (grid, start, end) = parse_file("Sz\nzE").unwrap();
+        assert_eq!(get_shortest_walk(&grid, start, end), None);
+        assert_eq!(get_shortest_trailhead(&grid, end), None);
+        assert!(Day12::part1(parse_file("Sz\nzE").unwrap()).is_err());
+    }
 }
@@ -1,27 +1,33 @@
-use std::cmp::Ordering;
-
-use crate::file::read_file;
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+    trace,
+};
 #[derive(Debug, Clone)]
-struct Array {
-    items: Option<Vec<Array>>,
-    number: Option<u32>,
+pub struct Array {
+    pub items: Option<Vec<Array>>,
+    pub number: Option<u32>,
 }
 
-impl ToString for Array {
-    fn to_string(&self) -> String {
+impl fmt::Display for Array {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(items) = &self.items {
-            return format!(
+            write!(
+                f,
                 "[{}]",
                 items
                     .iter()
                     .map(|i| i.to_string())
                     .collect::<Vec<String>>()
                     .join(",")
-            );
+            )
         } else if let Some(n) = self.number {
-            return n.to_string();
+            write!(f, "{}", n)
         } else {
-            return "".to_string();
+            Ok(())
         }
     }
 }
@@ -136,8 +142,8 @@ impl From<Vec<Array>> for Array {
     }
 }
 
-type Pair = (Array, Array);
-trait IsValid {
+pub type Pair = (Array, Array);
+pub trait IsValid {
     fn is_valid(&self) -> bool;
 }
 
@@ -147,25 +153,37 @@ impl IsValid for Pair {
     }
 }
 
-fn parse_line(text: &str) -> Array {
-    let mut chars = text.chars();
+/// Parses a single packet, reporting errors as if it were the first line
+pub fn parse_line(text: &str) -> Result<Array, ParseError> {
+    let error =
+        |column: usize, message: &str| ParseError::new(13, 0, text, message).at_column(column);
+    let number = |column: usize, digits: &[char]| {
+        digits
+            .iter()
+            .collect::<String>()
+            .parse::<u32>()
+            .map_err(|_| error(column - digits.len(), "number too large"))
+    };
     let mut char_stack: Vec<char> = Vec::new();
     let mut array_stack: Vec<Array> = Vec::new();
-    let mut last_array: Array = Array {
-        items: None,
-        number: None,
-    };
-    while let Some(c) = chars.next() {
-        // println!("{:?}", c);
-        // println!("{:?}", array_stack);
-        // println!("{:?}", char_stack);
+    let mut last_array: Option<Array> = None;
+    for (column, c) in text.chars().enumerate() {
+        trace!("{:?} {:?} {:?}", c, array_stack, char_stack);
+        // Outside of any list there's only room for the packet's own opening '['
+        if array_stack.is_empty() && last_array.is_some() {
+            return Err(error(column, "unexpected character after the packet"));
+        }
+        if array_stack.is_empty() && c != '[' {
+            return Err(error(column, "expected '['"));
+        }
         match c {
             '[' => array_stack.push(Array {
                 items: Some(Vec::new()),
                 number: None,
             }),
             ']' => {
-                if let Some(number) = char_stack.iter().collect::<String>().parse::<u32>().ok() {
+                if !char_stack.is_empty() {
+                    let number = number(column, &char_stack)?;
                     char_stack.truncate(0);
                     if let Some(last_array) = array_stack.last_mut() {
                         if let Some(items) = last_array.items.as_mut() {
@@ -177,21 +195,19 @@ fn parse_line(text: &str) -> Array {
                     }
                 }
 
-                let array = array_stack.pop().unwrap().clone();
-                last_array = array.clone();
+                let array = array_stack
+                    .pop()
+                    .ok_or_else(|| error(column, "unmatched ']'"))?;
+                last_array = Some(array.clone());
                 if let Some(last_array) = array_stack.last_mut() {
                     if let Some(items) = last_array.items.as_mut() {
-                        items.push(Array::from(array));
+                        items.push(array);
                     }
                 }
             }
             ',' => {
-                if char_stack.len() > 0 {
-                    let number = char_stack
-                        .iter()
-                        .collect::<String>()
-                        .parse::<u32>()
-                        .unwrap();
+                if !char_stack.is_empty() {
+                    let number = number(column, &char_stack)?;
                     char_stack.truncate(0);
 
                     if let Some(last_array) = array_stack.last_mut() {
@@ -202,41 +218,58 @@ fn parse_line(text: &str) -> Array {
                             });
                         }
                     }
-                } else {
                 }
             }
-            d => char_stack.push(d),
+            d if d.is_ascii_digit() => char_stack.push(d),
+            _ => return Err(error(column, "unexpected character")),
         }
     }
 
-    // println!("{:?}", last_array);
+    trace!("{:?}", last_array);
 
-    last_array
+    if !array_stack.is_empty() {
+        return Err(error(text.chars().count(), "unclosed '['"));
+    }
+    last_array.ok_or_else(|| error(0, "expected a packet"))
 }
 
-fn parse_file(text: &str) -> Vec<Pair> {
-    text.split("\n\n")
-        .map(|p| {
-            let mut lines = p.split("\n");
-            (
-                parse_line(lines.next().unwrap()),
-                parse_line(lines.next().unwrap()),
-            )
+pub fn parse_file(text: &str) -> Result<Vec<Pair>, ParseError> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .chunks(3)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let packet = |offset: usize| match chunk.get(offset) {
+                Some(line) => parse_line(line).map_err(|e| e.on_line(i * 3 + offset)),
+                None => Err(ParseError::input(
+                    13,
+                    format!("pair {} has no second packet", i + 1),
+                )),
+            };
+            if let Some(line) = chunk.get(2).filter(|l| !l.is_empty()) {
+                return Err(ParseError::new(
+                    13,
+                    i * 3 + 2,
+                    line,
+                    "expected a blank line between pairs",
+                ));
+            }
+            Ok((packet(0)?, packet(1)?))
         })
         .collect()
 }
 
-fn get_valid_pairs(pairs: &Vec<Pair>) -> usize {
+pub fn get_valid_pairs(pairs: &[Pair]) -> usize {
     let mut sum = 0;
-    for i in 0..pairs.len() {
-        if pairs[i].is_valid() {
+    for (i, pair) in pairs.iter().enumerate() {
+        if pair.is_valid() {
             sum += i + 1;
         }
     }
     sum
 }
 
-fn get_packets(pairs: &Vec<Pair>) -> Vec<Array> {
+pub fn get_packets(pairs: &[Pair]) -> Vec<Array> {
     let mut packets = Vec::new();
     for pair in pairs {
         packets.push(pair.0.clone());
@@ -249,16 +282,16 @@ fn get_packets(pairs: &Vec<Pair>) -> Vec<Array> {
     packets
 }
 
-fn get_sorted_packets(pairs: &Vec<Pair>) -> Vec<Array> {
+pub fn get_sorted_packets(pairs: &[Pair]) -> Vec<Array> {
     let mut packets = get_packets(pairs);
     packets.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    // for p in packets.iter() {
-    //     println!("{}", p.to_string());
-    // }
+    for p in packets.iter() {
+        trace!("{}", p);
+    }
     packets
 }
 
-fn get_decoder_key(pairs: &Vec<Pair>) -> usize {
+pub fn get_decoder_key(pairs: &[Pair]) -> usize {
     let packets = get_sorted_packets(pairs);
     let index1 = packets
         .iter()
@@ -274,37 +307,52 @@ fn get_decoder_key(pairs: &Vec<Pair>) -> usize {
     index1 * index2
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A random packet, nested at most `depth` lists deeper
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let items: Vec<String> = (0..rng.range(0..=4))
+        .map(|_| match depth > 0 && rng.one_in(3) {
+            true => packet(rng, depth - 1),
+            false => rng.range(0..=10).to_string(),
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
 
-    static TEST_STR: &str = "[1,1,3,1,1]
-[1,1,5,1,1]
+/// `size` random pairs of packets
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{}\n{}", packet(rng, 4), packet(rng, 4)))
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
 
-[[1],[2,3,4]]
-[[1],4]
+pub struct Day13;
 
-[9]
-[[8,7,6]]
+impl Solution for Day13 {
+    type Input<'a> = Vec<Pair>;
 
-[[4,4],4,4]
-[[4,4],4,4,4]
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
 
-[7,7,7,7]
-[7,7,7]
+    fn part1(pairs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_valid_pairs(&pairs).into())
+    }
 
-[]
-[3]
+    fn part2(pairs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_decoder_key(&pairs).into())
+    }
+}
 
-[[[]]]
-[[]]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let pairs = parse_file(TEST_STR);
+        let pairs = parse_file(TEST_STR).unwrap();
         assert_eq!(pairs.len(), 8);
         assert_eq!(pairs[0].0.items.as_ref().unwrap().len(), 5);
         assert_eq!(pairs[0].1.items.as_ref().unwrap().len(), 5);
@@ -312,6 +360,16 @@ mod tests {
         assert_eq!(pairs[1].1.items.as_ref().unwrap().len(), 2);
     }
 
+    #[test]
+    fn test_parse_line_errors() {
+        assert_eq!(parse_line("[1,[2]").unwrap_err().message, "unclosed '['");
+        for (line, column) in [("[1]2", 4), ("[1],[2]", 4), ("[1][2]", 4), ("5[1]", 1)] {
+            assert_eq!(parse_line(line).unwrap_err().column, column, "{}", line);
+        }
+        let error = parse_file("[1]\n[1,x]").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
     #[test]
     fn test_compare() {
         assert!((vec![1, 1, 3, 1, 1].into(), vec![1, 1, 5, 1, 1].into()).is_valid());
@@ -330,28 +388,13 @@ mod tests {
 
     #[test]
     fn test_get_valid_pairs() {
-        let pairs = parse_file(TEST_STR);
+        let pairs = parse_file(TEST_STR).unwrap();
         assert_eq!(get_valid_pairs(&pairs), 13);
     }
 
     #[test]
     fn test_get_decoder_key() {
-        let pairs = parse_file(TEST_STR);
+        let pairs = parse_file(TEST_STR).unwrap();
         assert_eq!(get_decoder_key(&pairs), 140);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let pairs = parse_file(&contents);
-
-    println!("{}", get_valid_pairs(&pairs));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let pairs = parse_file(&contents);
-    println!("{}", get_decoder_key(&pairs));
-}
@@ -1,98 +1,185 @@
-use crate::file::read_file;
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    generate::Rng,
+    geometry::Point,
+    grid::Grid,
+    parse::ParseError,
+    registry::Part,
+    solution::{Answer, Solution, SolveError},
+    trace,
+    visualize::{Frame, Recorder, Visualize},
+};
 
 #[derive(Debug, Clone, PartialEq)]
-enum PathState {
+pub enum PathState {
     Air,
     Sand,
     Rock,
 }
 
-type Grid = Vec<Vec<PathState>>;
-
-fn parse_file(text: &str) -> Grid {
-    let mut grid: Grid = (0..1000)
-        .map(|_| (0..1000).map(|_| PathState::Air).collect())
-        .collect();
+/// Where the sand pours in
+pub const SOURCE: Point = Point::new(500, 0);
 
-    text.lines().for_each(|l| {
-        let mut coords = l.split(" -> ");
-        let mut first_coord = coords
-            .next()
-            .unwrap()
-            .split(",")
-            .map(|c| c.parse::<usize>().unwrap());
-
-        let mut current_x = first_coord.next().unwrap();
-        let mut current_y = first_coord.next().unwrap();
-        for coord in coords {
-            let mut parts = coord.split(",").map(|c| c.parse::<usize>().unwrap());
-            let target_x = parts.next().unwrap();
-            let target_y = parts.next().unwrap();
-            // println!("{current_x},{current_y}->{target_x},{target_y}");
-
-            for x in current_x.min(target_x)..=target_x.max(current_x) {
-                for y in current_y.min(target_y)..=target_y.max(current_y) {
-                    // println!("{x},{y}");
-                    grid[x][y] = PathState::Rock;
-                }
-            }
+/// As much of the cave as rock and sand can reach, indexed by points in the puzzle's
+/// coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cave {
+    pub grid: Grid<PathState>,
+    /// The x of the grid's first column
+    pub left: isize,
+}
 
-            current_x = target_x;
-            current_y = target_y;
+impl Cave {
+    /// Room for `rocks`, and for a floor two below the lowest of them with the pile of sand
+    /// that can build up on it, which spreads one column either way for every row it falls
+    pub fn new(rocks: &[Point]) -> Cave {
+        let floor = rocks.iter().map(|p| p.y).max().unwrap_or(0) + 2;
+        let xs = rocks.iter().map(|p| p.x);
+        let left = xs.clone().min().unwrap_or(SOURCE.x).min(SOURCE.x - floor) - 1;
+        let right = xs.max().unwrap_or(SOURCE.x).max(SOURCE.x + floor) + 1;
+        Cave {
+            grid: Grid::new(
+                (right - left + 1) as usize,
+                floor as usize + 1,
+                PathState::Air,
+            ),
+            left,
         }
-    });
+    }
 
-    grid
+    pub fn at(&self, point: Point) -> Option<&PathState> {
+        self.grid.at(point - Point::new(self.left, 0))
+    }
 }
 
-fn add_floor(grid: &mut Grid) -> &Grid {
-    let mut max_y = 0;
-    for x in 0..grid.len() {
-        for y in 0..grid[x].len() {
-            if grid[x][y] == PathState::Rock && y > max_y {
-                max_y = y;
-            }
-        }
+impl Index<Point> for Cave {
+    type Output = PathState;
+
+    fn index(&self, point: Point) -> &PathState {
+        &self.grid[point - Point::new(self.left, 0)]
     }
+}
 
-    for x in 0..grid.len() {
-        grid[x][max_y + 2] = PathState::Rock
+impl IndexMut<Point> for Cave {
+    fn index_mut(&mut self, point: Point) -> &mut PathState {
+        &mut self.grid[point - Point::new(self.left, 0)]
     }
+}
 
-    grid
+pub fn parse_coord(i: usize, line: &str, coord: &str) -> Result<Point, ParseError> {
+    let error = |message: &str| ParseError::new(14, i, line, message).at(coord);
+    let (x, y) = coord
+        .split_once(',')
+        .ok_or_else(|| error("expected a coordinate like 498,4"))?;
+    let x = x.parse::<isize>().map_err(|_| error("invalid x"))?;
+    let y = y.parse::<isize>().map_err(|_| error("invalid y"))?;
+    // Sand pours in from the top, so nothing can be above it
+    if y < 0 {
+        return Err(error("coordinate is above the cave"));
+    }
+    Ok(Point::new(x, y))
 }
 
-fn move_sand(grid: &Grid, sand: (usize, usize)) -> Option<(usize, usize)> {
-    if sand.1 >= grid[sand.0].len() - 1 {
-        return None;
+pub fn parse_file(text: &str) -> Result<Cave, ParseError> {
+    let mut rocks = Vec::new();
+    for (i, l) in text.lines().enumerate() {
+        let mut coords = l.split(" -> ");
+        let mut current = parse_coord(i, l, coords.next().unwrap())?;
+        rocks.push(current);
+        for coord in coords {
+            let target = parse_coord(i, l, coord)?;
+            trace!("{:?}->{:?}", current, target);
+            if current.x != target.x && current.y != target.y {
+                return Err(ParseError::new(14, i, l, "rock paths must be straight").at(coord));
+            }
+
+            let step = (target - current).signum();
+            while current != target {
+                current += step;
+                rocks.push(current);
+            }
+        }
     }
-    if grid[sand.0][sand.1 + 1] == PathState::Air {
-        return Some((sand.0, sand.1 + 1));
+
+    let mut cave = Cave::new(&rocks);
+    for rock in rocks {
+        cave[rock] = PathState::Rock;
     }
-    if grid[sand.0 - 1][sand.1 + 1] == PathState::Air {
-        return Some((sand.0 - 1, sand.1 + 1));
+    Ok(cave)
+}
+
+/// Lays the floor along the bottom row, two below the lowest rock
+pub fn add_floor(cave: &mut Cave) -> &Cave {
+    let floor = cave.grid.height() - 1;
+    for x in 0..cave.grid.width() {
+        cave.grid[(x, floor)] = PathState::Rock
     }
-    if grid[sand.0 + 1][sand.1 + 1] == PathState::Air {
-        return Some((sand.0 + 1, sand.1 + 1));
+
+    cave
+}
+
+/// Where sand at `sand` goes next, or `None` if it falls out of the cave
+pub fn move_sand(cave: &Cave, sand: Point) -> Option<Point> {
+    for step in [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)] {
+        match cave.at(sand + step) {
+            Some(PathState::Air) => return Some(sand + step),
+            Some(_) => (),
+            None => return None,
+        }
     }
 
-    return Some(sand);
+    Some(sand)
 }
 
-fn drop_sand(grid: &mut Grid) -> usize {
+pub fn drop_sand(cave: &mut Cave) -> usize {
+    drop_sand_recorded(cave, &mut ())
+}
+
+/// Draws the cave as in the puzzle, cropped to where sand can reach from the source
+fn draw_cave(cave: &Cave) -> Frame {
+    // Sand spreads at most one column sideways for every row it falls
+    let depth = (0..cave.grid.height())
+        .rev()
+        .find(|y| cave.grid.row(*y).contains(&PathState::Rock))
+        .unwrap_or(0);
+    let left = (SOURCE.x - cave.left) as usize - depth;
+    let mut frame = cave
+        .grid
+        .crop(left, 0, depth * 2 + 1, depth + 1)
+        .map(|state| match state {
+            PathState::Air => '.',
+            PathState::Sand => 'o',
+            PathState::Rock => '#',
+        });
+    if frame[(depth, 0)] == '.' {
+        frame[(depth, 0)] = '+';
+    }
+    frame
+}
+
+/// Pours sand until it falls out of the cave or blocks the source, recording a frame
+/// whenever a grain comes to rest
+pub fn drop_sand_recorded(cave: &mut Cave, recorder: &mut dyn Recorder) -> usize {
     let mut sand_count = 0;
-    let mut current_sand = (500, 0);
+    let mut current_sand = SOURCE;
+    if recorder.is_recording() {
+        recorder.record(&draw_cave(cave));
+    }
     loop {
-        let new_sand = move_sand(grid, current_sand);
+        let new_sand = move_sand(cave, current_sand);
         match new_sand {
             Some(sand) => {
                 if sand == current_sand {
-                    grid[sand.0][sand.1] = PathState::Sand;
+                    cave[sand] = PathState::Sand;
                     sand_count += 1;
-                    if current_sand == (500, 0) {
+                    if recorder.is_recording() {
+                        recorder.record(&draw_cave(cave));
+                    }
+                    if current_sand == SOURCE {
                         return sand_count;
                     }
-                    current_sand = (500, 0);
+                    current_sand = SOURCE;
                 } else {
                     current_sand = sand;
                 }
@@ -102,47 +189,121 @@ fn drop_sand(grid: &mut Grid) -> usize {
     }
 }
 
+/// `size` random rock paths below the source, each a few straight segments long
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let mut point = Point::new(rng.range_signed(470..=530), rng.range_signed(10..=160));
+            let mut coords = vec![point];
+            let mut horizontal = rng.one_in(2);
+            for _ in 0..rng.range(1..=4) {
+                let step = rng.range_signed(-10..=10);
+                point = match horizontal {
+                    true => Point::new((point.x + step).clamp(400, 600), point.y),
+                    false => Point::new(point.x, (point.y + step).clamp(2, 170)),
+                };
+                coords.push(point);
+                horizontal = !horizontal;
+            }
+            coords
+                .iter()
+                .map(|p| format!("{},{}", p.x, p.y))
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input<'a> = Cave;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(mut cave: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(drop_sand(&mut cave).into())
+    }
+
+    fn part2(mut cave: Self::Input<'_>) -> Result<Answer, SolveError> {
+        add_floor(&mut cave);
+        Ok(drop_sand(&mut cave).into())
+    }
+}
+
+impl Visualize for Day14 {
+    fn visualize(mut cave: Self::Input<'_>, part: Part, recorder: &mut dyn Recorder) {
+        if part == Part::Two {
+            add_floor(&mut cave);
+        }
+        drop_sand_recorded(&mut cave, recorder);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::Frames;
 
-    static TEST_STR: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let grid = parse_file(TEST_STR);
-        assert_eq!(grid[498][4], PathState::Rock);
-        assert_eq!(grid[498][5], PathState::Rock);
-        assert_eq!(grid[499][6], PathState::Air);
-        assert_eq!(grid[494][9], PathState::Rock);
+        let cave = parse_file(TEST_STR).unwrap();
+        assert_eq!(cave[Point::new(498, 4)], PathState::Rock);
+        assert_eq!(cave[Point::new(498, 5)], PathState::Rock);
+        assert_eq!(cave[Point::new(499, 6)], PathState::Air);
+        assert_eq!(cave[Point::new(494, 9)], PathState::Rock);
     }
 
     #[test]
     fn test_drop_sand() {
-        let mut grid = parse_file(TEST_STR);
-        assert_eq!(drop_sand(&mut grid), 24);
+        let mut cave = parse_file(TEST_STR).unwrap();
+        assert_eq!(drop_sand(&mut cave), 24);
     }
 
     #[test]
     fn test_drop_sand_to_floor() {
-        let mut grid = parse_file(TEST_STR);
-        add_floor(&mut grid);
-        assert_eq!(drop_sand(&mut grid), 93);
+        let mut cave = parse_file(TEST_STR).unwrap();
+        add_floor(&mut cave);
+        assert_eq!(drop_sand(&mut cave), 93);
     }
-}
 
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let mut grid = parse_file(&contents);
-    println!("{}", drop_sand(&mut grid));
-}
+    #[test]
+    fn test_drop_sand_to_floor_beyond_rocks() {
+        // The pile on the floor spreads much wider than the rock
+        let mut cave = parse_file("1200,3 -> 1200,4").unwrap();
+        assert_eq!(cave[Point::new(1200, 4)], PathState::Rock);
+        assert_eq!(drop_sand(&mut cave.clone()), 0);
+        add_floor(&mut cave);
+        assert_eq!(drop_sand(&mut cave), 36);
+    }
 
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let mut grid = parse_file(&contents);
-    add_floor(&mut grid);
-    println!("{}", drop_sand(&mut grid));
+    #[test]
+    fn test_drop_sand_frames() {
+        let mut cave = parse_file(TEST_STR).unwrap();
+        let mut frames = Frames::default();
+        assert_eq!(drop_sand_recorded(&mut cave, &mut frames), 24);
+        assert_eq!(frames.0.len(), 25);
+        let last = frames.0[24].render(|c| *c);
+        let lines: Vec<&str> = last.lines().map(|l| &l[3..13]).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "......+...",
+                "..........",
+                "......o...",
+                ".....ooo..",
+                "....#ooo##",
+                "...o#ooo#.",
+                "..###ooo#.",
+                "....oooo#.",
+                ".o.ooooo#.",
+                "#########.",
+            ]
+        );
+    }
 }
@@ -2,192 +2,244 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
-use crate::file::read_file;
+use crate::{
+    cancel::{self, Cancelled, Token},
+    generate::Rng,
+    geometry::Point,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Debug, Clone, PartialEq)]
-enum GridState {
+pub enum GridState {
     Sensor,
     Beacon,
 }
 
 #[derive(Debug)]
-struct Sensor {
-    x: isize,
-    y: isize,
-    range: usize,
+pub struct Sensor {
+    pub position: Point,
+    pub range: usize,
 }
 
 impl Sensor {
-    fn distance(&self, x: isize, y: isize) -> usize {
-        self.x.abs_diff(x) + self.y.abs_diff(y)
+    pub fn distance(&self, point: &Point) -> usize {
+        self.position.manhattan(point)
     }
 
-    fn is_in_range(&self, x: isize, y: isize) -> bool {
-        let distance = self.distance(x, y);
+    pub fn is_in_range(&self, point: &Point) -> bool {
+        let distance = self.distance(point);
         distance <= self.range
     }
 }
 
-type Row = HashMap<isize, GridState>;
-type Grid = HashMap<isize, Row>;
-
-fn grid_set(grid: &mut Grid, x: isize, y: isize, v: GridState) {
-    grid.entry(x).or_insert_with(|| HashMap::new()).insert(y, v);
-}
+/// The known sensors and beacons
+pub type Grid = HashMap<Point, GridState>;
 
-fn parse_file(text: &str) -> (Grid, Vec<Sensor>) {
+pub fn parse_file(text: &str) -> Result<(Grid, Vec<Sensor>), ParseError> {
     let mut grid: Grid = HashMap::new();
 
     let regex = Regex::new(
-        r"Sensor at x=(?P<sx>(-|\d)+), y=(?P<sy>(-|\d)+): closest beacon is at x=(?P<bx>(-|\d)+), y=(?P<by>(-|\d)+)",
+        r"^Sensor at x=(?P<sx>-?\d+), y=(?P<sy>-?\d+): closest beacon is at x=(?P<bx>-?\d+), y=(?P<by>-?\d+)$",
     ).unwrap();
     let mut sensors = Vec::new();
-    text.lines().for_each(|l| {
-        let captures = regex.captures(l).unwrap();
-        let sensor = (
-            captures
-                .name("sx")
-                .unwrap()
-                .as_str()
-                .parse::<isize>()
-                .unwrap(),
-            captures
-                .name("sy")
-                .unwrap()
-                .as_str()
-                .parse::<isize>()
-                .unwrap(),
-        );
-
-        let beacon = (
-            captures
-                .name("bx")
-                .unwrap()
-                .as_str()
-                .parse::<isize>()
-                .unwrap(),
-            captures
-                .name("by")
-                .unwrap()
-                .as_str()
-                .parse::<isize>()
-                .unwrap(),
-        );
-        grid_set(&mut grid, sensor.0, sensor.1, GridState::Sensor);
-        grid_set(&mut grid, beacon.0, beacon.1, GridState::Beacon);
+    for (i, l) in text.lines().enumerate() {
+        let captures = regex.captures(l).ok_or_else(|| {
+            ParseError::new(
+                15,
+                i,
+                l,
+                "expected 'Sensor at x=.., y=..: closest beacon is at x=.., y=..'",
+            )
+        })?;
+        let number = |name: &str| {
+            let m = captures.name(name).unwrap().as_str();
+            m.parse::<isize>()
+                .map_err(|_| ParseError::new(15, i, l, "number too large").at(m))
+        };
+        let sensor = Point::new(number("sx")?, number("sy")?);
+        let beacon = Point::new(number("bx")?, number("by")?);
+        grid.insert(sensor, GridState::Sensor);
+        grid.insert(beacon, GridState::Beacon);
         sensors.push(Sensor {
-            x: sensor.0,
-            y: sensor.1,
-            range: beacon.0.abs_diff(sensor.0) + beacon.1.abs_diff(sensor.1),
+            position: sensor,
+            range: sensor.manhattan(&beacon),
         })
-    });
+    }
 
-    (grid, sensors)
+    Ok((grid, sensors))
 }
 
-fn count_eliminated(grid: &mut Grid, sensors: &Vec<Sensor>, y: isize) -> usize {
+/// The number of positions on row `y` where a beacon can't be, giving up once `token` is cancelled
+pub fn count_eliminated(
+    grid: &mut Grid,
+    sensors: &[Sensor],
+    y: isize,
+    token: &Token,
+) -> Result<usize, Cancelled> {
+    // Nothing outside the sensors' reach can be ruled out
+    let min_x = sensors
+        .iter()
+        .map(|s| s.position.x - s.range as isize)
+        .min();
+    let max_x = sensors
+        .iter()
+        .map(|s| s.position.x + s.range as isize)
+        .max();
+    let (Some(min_x), Some(max_x)) = (min_x, max_x) else {
+        return Ok(0);
+    };
+
+    let progress = token.progress_bar(min_x.abs_diff(max_x) as u64 + 1, "day 15 columns");
     let mut count = 0;
-    for x in -1_000_000..1_000_000 {
-        if grid.get(&x).and_then(|c| c.get(&y)) == None {
-            match sensors.iter().find(|s| s.is_in_range(x, y)) {
-                Some(_) => count += 1,
-                None => (),
-            }
+    for x in min_x..=max_x {
+        if x % 4096 == 0 {
+            token.check()?;
+            progress.set_position(min_x.abs_diff(x) as u64);
+        }
+        let point = Point::new(x, y);
+        if !grid.contains_key(&point) && sensors.iter().any(|s| s.is_in_range(&point)) {
+            count += 1;
         }
     }
+    progress.finish_and_clear();
 
-    count
+    Ok(count)
 }
 
-fn find_beacon(
-    grid: &Grid,
-    sensors: &Vec<Sensor>,
-    max_x: isize,
-    max_y: isize,
-) -> Option<(isize, isize)> {
+/// The only point in range that no sensor can see, searching just outside each sensor's edge
+pub fn find_beacon(grid: &Grid, sensors: &[Sensor], max_x: isize, max_y: isize) -> Option<Point> {
     for sensor in sensors {
-        for x in (-1 * sensor.range as isize - 1)..=(sensor.range as isize + 1) {
+        let edge = sensor.range as isize + 1;
+        for x in -edge..=edge {
             for y in [-1, 1] {
-                let x_y = (
-                    sensor.x + x,
-                    sensor.y + y * ((sensor.range as isize + 1).abs_diff(x) as isize),
-                );
-                if (0..=max_x).contains(&x_y.0)
-                    && (0..=max_y).contains(&x_y.1)
-                    && grid.get(&x_y.0).and_then(|c| c.get(&x_y.1)) == None
+                let point = sensor.position + Point::new(x, y * (edge - x.abs()));
+                if (0..=max_x).contains(&point.x)
+                    && (0..=max_y).contains(&point.y)
+                    && !grid.contains_key(&point)
+                    && !sensors.iter().any(|s| s.is_in_range(&point))
                 {
-                    match sensors.iter().find(|s| s.is_in_range(x_y.0, x_y.1)) {
-                        Some(_) => (),
-                        None => return Some(x_y),
-                    }
+                    return Some(point);
                 }
             }
         }
     }
     None
 }
-fn find_tuning(grid: &Grid, sensors: &Vec<Sensor>, max_x: isize, max_y: isize) -> Option<isize> {
+pub fn find_tuning(grid: &Grid, sensors: &[Sensor], max_x: isize, max_y: isize) -> Option<isize> {
     if let Some(beacon) = find_beacon(grid, sensors, max_x, max_y) {
-        return Some(beacon.0 * 4000000 + beacon.1);
+        return Some(beacon.x * 4000000 + beacon.y);
     }
     None
 }
 
+/// `size` random sensors in the 4000000 square searched by part 2. Each one's range stops just
+/// short of the same hidden point, so the distress beacon is always somewhere to be found.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let hidden = Point::new(
+        rng.range_signed(0..=4_000_000),
+        rng.range_signed(0..=4_000_000),
+    );
+    (0..size.max(1))
+        .map(|_| {
+            let sensor = loop {
+                let sensor = Point::new(
+                    rng.range_signed(0..=4_000_000),
+                    rng.range_signed(0..=4_000_000),
+                );
+                if sensor.manhattan(&hidden) >= 2 {
+                    break sensor;
+                }
+            };
+            let range = sensor.manhattan(&hidden) as isize - 1;
+            let dx = rng.range_signed(-range..=range);
+            let dy = (range - dx.abs()) * if rng.one_in(2) { 1 } else { -1 };
+            let beacon = sensor + Point::new(dx, dy);
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                sensor.x, sensor.y, beacon.x, beacon.y
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input<'a> = (Grid, Vec<Sensor>);
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1((mut grid, sensors): Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(
+            count_eliminated(&mut grid, &sensors, 2_000_000, &cancel::current())
+                .unwrap_or_else(|c| c.abort())
+                .into(),
+        )
+    }
+
+    fn part2((grid, sensors): Self::Input<'_>) -> Result<Answer, SolveError> {
+        find_tuning(&grid, &sensors, 4_000_000, 4_000_000)
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::unsolvable(15, "every spot in range is seen by a sensor"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+    static TEST_STR: &str = include_str!("example1.txt");
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_file("Sensor at x=2, y=18: closest beacon at x=-2, y=15").unwrap_err();
+        assert_eq!((error.day, error.line), (15, 1));
+    }
 
     #[test]
     fn test_parse_file() {
-        let (grid, sensors) = parse_file(TEST_STR);
-        assert_eq!(grid[&-2][&15], GridState::Beacon);
-        assert_eq!(grid[&13][&2], GridState::Sensor);
-        assert_eq!(grid[&21][&22], GridState::Beacon);
-        assert_eq!(sensors[3].x, 12);
-        assert_eq!(sensors[3].y, 14);
+        let (grid, sensors) = parse_file(TEST_STR).unwrap();
+        assert_eq!(grid[&Point::new(-2, 15)], GridState::Beacon);
+        assert_eq!(grid[&Point::new(13, 2)], GridState::Sensor);
+        assert_eq!(grid[&Point::new(21, 22)], GridState::Beacon);
+        assert_eq!(sensors[3].position, Point::new(12, 14));
+        assert_eq!(sensors[3].range, 4);
+    }
+
+    #[test]
+    fn test_count_eliminated_far_out() {
+        // Further from the origin than a million columns, with the sensor and beacon among the
+        // five it can see
+        let (mut grid, sensors) =
+            parse_file("Sensor at x=1000005, y=10: closest beacon is at x=1000007, y=10").unwrap();
+        assert_eq!(
+            count_eliminated(&mut grid, &sensors, 10, &Token::new()),
+            Ok(3)
+        );
     }
 
     #[test]
     fn test_count_eliminated() {
-        let (mut grid, sensors) = parse_file(TEST_STR);
-        assert_eq!(count_eliminated(&mut grid, &sensors, 10), 26);
+        let (mut grid, sensors) = parse_file(TEST_STR).unwrap();
+        assert_eq!(
+            count_eliminated(&mut grid, &sensors, 10, &Token::new()),
+            Ok(26)
+        );
+        let token = Token::new();
+        token.cancel();
+        assert_eq!(
+            count_eliminated(&mut grid, &sensors, 10, &token),
+            Err(Cancelled)
+        );
     }
 
     #[test]
     fn test_find_tuning() {
-        let (grid, sensors) = parse_file(TEST_STR);
+        let (grid, sensors) = parse_file(TEST_STR).unwrap();
         assert_eq!(find_tuning(&grid, &sensors, 20, 20), Some(56000011));
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let (mut grid, sensors) = parse_file(&contents);
-    println!("{}", count_eliminated(&mut grid, &sensors, 2_000_000));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let (mut grid, sensors) = parse_file(&contents);
-    println!(
-        "{}",
-        find_tuning(&mut grid, &sensors, 4_000_000, 4_000_000).unwrap()
-    );
-}
@@ -1,79 +1,59 @@
-use std::{
-    collections::{HashMap, HashSet},
-    ops::Deref,
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    cancel::{self, Cancelled, Token},
+    generate::Rng,
+    info,
+    parse::ParseError,
+    pathfinding::bfs,
+    solution::{Answer, Solution, SolveError},
 };
-
-use crate::file::read_file;
-use indicatif::ProgressBar;
-use itertools::Itertools;
-use rayon::prelude::{ParallelBridge, ParallelIterator};
 use regex::Regex;
 
 #[derive(Debug, Default, Clone)]
-struct Valve<'a> {
-    id: &'a str,
-    outs: HashSet<&'a str>,
-    paths: HashMap<&'a str, Vec<&'a str>>,
-    is_open: bool,
-    rate: usize,
+pub struct Valve<'a> {
+    pub id: &'a str,
+    pub outs: HashSet<&'a str>,
+    pub paths: HashMap<&'a str, Vec<&'a str>>,
+    pub rate: usize,
 }
 
 #[derive(Debug, Default, Clone)]
-struct ValveGraph<'a> {
-    valves: HashMap<&'a str, Valve<'a>>,
-    pressure_released: usize,
-    minutes_left: u8,
-    current_valve: &'a str,
+pub struct ValveGraph<'a> {
+    pub valves: HashMap<&'a str, Valve<'a>>,
 }
 
 impl<'a> ValveGraph<'a> {
-    fn add_valve(&mut self, valve: Valve<'a>) {
+    pub fn add_valve(&mut self, valve: Valve<'a>) {
         self.valves.insert(valve.id, valve);
     }
 
-    fn tick(&mut self) {
-        if self.minutes_left == 0 {
-            return;
-        }
-        self.valves
-            .values()
-            .filter(|v| v.is_open)
-            .for_each(|v| self.pressure_released += v.rate);
-
-        self.minutes_left -= 1
-    }
-
-    fn calculate_all_paths(&mut self) {
-        let keys: Vec<&str> = self.valves.keys().map(|k| *k).collect();
+    pub fn calculate_all_paths(&mut self) {
+        let keys: Vec<&str> = self.valves.keys().copied().collect();
         for valve in keys {
             self.calculate_paths(valve)
         }
     }
 
-    fn calculate_paths(&mut self, valve: &str) {
-        let keys: Vec<&str> = self.valves.keys().map(|k| *k).collect();
-        let mut paths: HashMap<&str, Vec<&str>> =
-            HashMap::from_iter(keys.iter().map(|k| (*k, Vec::new())));
-
-        let mut queue: Vec<&str> = vec![valve];
-        while let Some(current) = queue.pop() {
-            let current_path = paths[current].clone();
-            let outs = self.valves.get(current).unwrap().outs.clone();
-            for out in outs {
-                if paths.get(out).unwrap().len() == 0
-                    || paths.get(out).unwrap().len() > current_path.len() + 1
-                {
-                    let mut path = current_path.clone();
-                    path.push(out);
-                    paths.insert(out, path);
-                    queue.push(out);
-                }
-            }
-        }
+    /// The shortest route from `valve` to every other valve, excluding `valve` itself
+    pub fn calculate_paths(&mut self, valve: &str) {
+        let shortest = bfs([self.valves[valve].id], |current| {
+            self.valves[current]
+                .outs
+                .iter()
+                .copied()
+                .collect::<Vec<&str>>()
+        });
+        let paths = shortest
+            .distances()
+            .keys()
+            .map(|to| (*to, shortest.path_to(to).unwrap()[1..].to_vec()))
+            .collect();
         self.valves.get_mut(valve).unwrap().paths = paths;
     }
 
-    fn get_path_value(&self, from: &str, to: &str) -> isize {
+    pub fn get_path_value(&self, from: &str, to: &str) -> isize {
         let mut value = 0;
         let path = self.valves.get(from).unwrap().paths.get(to).unwrap();
         for v in path {
@@ -81,214 +61,373 @@ impl<'a> ValveGraph<'a> {
         }
         value - (path.len() as isize * 2)
     }
-    // fn get_expected_pressure(&self, from: &str, to: &str, minutes: u8) -> usize {
-    //     let path_length = if from == to {
-    //         0
-    //     } else {
-    //         self.valves.get(from).unwrap().paths.get(to).unwrap().len()
-    //     };
-    //     if (minutes as usize) <= path_length {
-    //         return 0;
-    //     }
-    //     (minutes as usize - path_length - 1) * self.valves.get(to).unwrap().rate
-    // }
-
-    // fn get_best_pressure_path(&mut self, from: &str, minutes: u8) -> Vec<&str> {
-    //     let mut best_pressure = 0;
-    //     let mut best_pressure_valve = "";
-
-    //     for to in self.valves.keys() {
-    //         if self.valves.get(to).unwrap().is_open {
-    //             continue;
-    //         }
-    //         let pressure = self.get_expected_pressure(from, to, minutes);
-    //         // println!("Current best {}", best_pressure);
-    //         // println!("Pressure {} for valve {}", pressure, to);
-    //         if pressure > best_pressure {
-    //             best_pressure = pressure;
-    //             best_pressure_valve = *to;
-    //         }
-    //     }
-
-    //     if best_pressure_valve == from {
-    //         Vec::new()
-    //     } else {
-    //         self.valves
-    //             .get(from)
-    //             .unwrap()
-    //             .paths
-    //             .get(best_pressure_valve)
-    //             .unwrap()
-    //             .deref()
-    //             .to_vec()
-    //     }
-    // }
-
-    // fn execute(&mut self) -> usize {
-    //     let mut current_valve = "AA";
-    //     self.minutes_left = 30;
-
-    //     // let mut clone = self.clone();
-    //     while self.minutes_left > 0 {
-    //         let best_path = {
-    //             let ref this = self;
-    //             let minutes = self.minutes_left;
-    //             let mut best_pressure = 0;
-    //             let mut best_pressure_valve = "";
-
-    //             for to in this
-    //                 .valves
-    //                 .keys()
-    //                 .filter(|v| this.valves.get(**v).unwrap().rate > 0)
-    //             {
-    //                 if this.valves.get(to).unwrap().is_open {
-    //                     continue;
-    //                 }
-    //                 let pressure = this.get_expected_pressure(current_valve, to, minutes);
-    //                 // println!("Current best {}", best_pressure);
-    //                 // println!("Pressure {} for valve {}", pressure, to);
-    //                 if pressure > best_pressure {
-    //                     best_pressure = pressure;
-    //                     best_pressure_valve = *to;
-    //                 }
-    //             }
-
-    //             if best_pressure_valve == "" || best_pressure_valve == current_valve {
-    //                 Vec::new()
-    //             } else {
-    //                 this.valves
-    //                     .get(current_valve)
-    //                     .unwrap()
-    //                     .paths
-    //                     .get(best_pressure_valve)
-    //                     .unwrap()
-    //                     .deref()
-    //                     .to_vec()
-    //             }
-    //         };
-    //         if best_path.len() == 0 {
-    //             break;
-    //         }
-    //         let to_valve = best_path.last().unwrap();
-    //         // println!("Minutes left {} path {:?}", self.minutes_left, best_path);
-    //         for _ in 0..best_path.len() {
-    //             self.tick();
-    //         }
-    //         self.tick();
-    //         self.valves.get_mut(to_valve).unwrap().is_open = true;
-    //         // clone.valves.get_mut(to_valve).unwrap().is_open = true;
-    //         current_valve = to_valve;
-    //     }
-
-    //     self.pressure_released
-    // }
-
-    fn calculate_permutations(&self) -> Vec<Vec<&str>> {
-        let non_zero_valves: Vec<&str> = self
+
+    /// The valves worth opening, with the minutes it takes to walk to each of them from `start`
+    /// and from each other. Needs `calculate_all_paths` first.
+    fn network(&self, start: &str) -> Network<'a> {
+        let mut ids: Vec<&'a str> = self
             .valves
-            .keys()
-            .filter(|k| self.valves.get(*k).unwrap().rate > 0)
-            .map(|k| *k)
-            .collect();
-        // println!("{:?}", non_zero_valves);
-        let permutations = non_zero_valves
-            .iter()
-            .permutations(non_zero_valves.len())
-            .map(|p| p.iter().map(|p| **p).collect())
+            .values()
+            .filter(|v| v.rate > 0)
+            .map(|v| v.id)
             .collect();
+        // `parse_file` keeps this within MAX_RATED, so each fits a bit of the opened set
+        ids.sort();
+        let distance = |from: &str, to: &str| match from == to {
+            true => 0,
+            false => self.valves[from].paths.get(to).map_or(usize::MAX, Vec::len),
+        };
+        Network {
+            rates: ids.iter().map(|id| self.valves[id].rate).collect(),
+            from_start: ids.iter().map(|id| distance(start, id)).collect(),
+            distances: ids
+                .iter()
+                .map(|from| ids.iter().map(|to| distance(from, to)).collect())
+                .collect(),
+            ids,
+        }
+    }
 
-        permutations
+    /// For every set of valves one agent can open within `minutes` of leaving `start`, the
+    /// route opening exactly that set which releases the most pressure
+    pub fn best_routes(&self, start: &str, minutes: usize) -> HashMap<u64, Route<'a>> {
+        let network = self.network(start);
+        network
+            .explore(minutes, &cancel::current())
+            .unwrap_or_else(|c| c.abort())
+            .into_iter()
+            .map(|(opened, (pressure, route))| {
+                let valves = route.iter().map(|i| network.ids[*i]).collect();
+                (opened, Route { valves, pressure })
+            })
+            .collect()
     }
 
-    fn execute(&mut self, sequence: &Vec<&str>) -> usize {
-        // let mut minutes_left = 30;
-        // let mut pressure_released = 0;
-        // let mut current_valve = "AA";
-        // println!("Executing {:?}", sequence);
-        self.minutes_left = 26;
-        self.pressure_released = 0;
-        self.current_valve = "AA";
-        for valve in self.valves.iter_mut() {
-            valve.1.is_open = false;
-        }
+    /// The route one agent can take in `minutes` from `start` that releases the most pressure
+    pub fn best_route(&self, start: &str, minutes: usize) -> Route<'a> {
+        self.best_routes(start, minutes)
+            .into_iter()
+            .max_by_key(|(opened, route)| (route.pressure, Reverse(*opened)))
+            .map(|(_, route)| route)
+            .unwrap_or_default()
+    }
 
-        let mut to_valves = sequence.iter();
-        while self.minutes_left > 0 && to_valves.len() > 0 {
-            let next_valve = to_valves.next().unwrap();
-            let path = self
-                .valves
-                .get(self.current_valve)
-                .unwrap()
-                .paths
-                .get(next_valve)
-                .unwrap()
-                .clone();
-            for _ in 0..path.len() {
-                self.tick()
+    /// The most pressure two agents sharing the same `minutes` can release from `start` by
+    /// opening different valves, with each agent's route
+    pub fn best_pair(&self, start: &str, minutes: usize) -> (usize, Route<'a>, Route<'a>) {
+        let mut routes: Vec<(u64, Route<'a>)> =
+            self.best_routes(start, minutes).into_iter().collect();
+        routes.sort_by_key(|(_, route)| Reverse(route.pressure));
+
+        // The empty route pairs with anything, so there is always a best pair
+        let mut best = (0, 0, 0);
+        for (i, (opened, route)) in routes.iter().enumerate() {
+            if route.pressure * 2 <= best.0 {
+                break;
+            }
+            // Sorted by pressure, so the first partner that doesn't overlap is the best one
+            if let Some(j) = routes[i..]
+                .iter()
+                .position(|(other, _)| opened & other == 0)
+            {
+                let pressure = route.pressure + routes[i + j].1.pressure;
+                if pressure > best.0 {
+                    best = (pressure, i, i + j);
+                }
             }
-            self.tick();
-            self.current_valve = path.last().unwrap();
-            self.valves.get_mut(self.current_valve).unwrap().is_open = true;
-        }
-        while self.minutes_left > 0 {
-            self.tick();
         }
+        let (pressure, i, j) = best;
+        (pressure, routes[i].1.clone(), routes[j].1.clone())
+    }
+}
+
+/// The valves one agent opens, in order, and the pressure they release before time runs out
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Route<'a> {
+    pub valves: Vec<&'a str>,
+    pub pressure: usize,
+}
+
+/// The valves with a flow rate, and how far apart they are, leaving out every other valve
+struct Network<'a> {
+    ids: Vec<&'a str>,
+    rates: Vec<usize>,
+    from_start: Vec<usize>,
+    distances: Vec<Vec<usize>>,
+}
 
-        // println!("{}", self.pressure_released);
+impl Network<'_> {
+    /// The minutes left after walking from `at`, or the start, to `next` and opening it, if
+    /// there is any time left to release pressure
+    fn left_after(&self, at: Option<usize>, next: usize, minutes: usize) -> Option<usize> {
+        let distance = match at {
+            Some(at) => self.distances[at][next],
+            None => self.from_start[next],
+        };
+        minutes
+            .checked_sub(distance.saturating_add(1))
+            .filter(|left| *left > 0)
+    }
 
-        self.pressure_released
+    /// The most pressure, and a route releasing it, for every set of valves that can be
+    /// opened within `minutes`. Routes grow a valve at a time, keeping only the best one to
+    /// each valve with the same valves open and minutes left, so the work is bounded by those
+    /// states rather than by every order the valves could be opened in.
+    fn explore(
+        &self,
+        minutes: usize,
+        token: &Token,
+    ) -> Result<HashMap<u64, (usize, Vec<usize>)>, Cancelled> {
+        type State = (Option<usize>, u64, usize);
+        let mut best: HashMap<u64, (usize, Vec<usize>)> = HashMap::new();
+        let mut states: HashMap<State, (usize, Vec<usize>)> =
+            HashMap::from([((None, 0, minutes), (0, Vec::new()))]);
+        while !states.is_empty() {
+            let mut longer: HashMap<State, (usize, Vec<usize>)> = HashMap::new();
+            for ((at, opened, minutes), (pressure, route)) in states {
+                token.check()?;
+                for next in (0..self.ids.len()).filter(|i| opened & 1 << i == 0) {
+                    let Some(left) = self.left_after(at, next, minutes) else {
+                        continue;
+                    };
+                    // Every valve here has a flow rate and time left, so this is never 0
+                    let pressure = pressure + left * self.rates[next];
+                    let state = longer
+                        .entry((Some(next), opened | 1 << next, left))
+                        .or_default();
+                    if pressure > state.0 {
+                        *state = (pressure, [&route[..], &[next]].concat());
+                    }
+                }
+                let entry = best.entry(opened).or_default();
+                if pressure > entry.0 {
+                    *entry = (pressure, route);
+                }
+            }
+            states = longer;
+        }
+        Ok(best)
     }
 }
 
-fn parse_file(text: &str) -> ValveGraph {
+/// The most valves with a flow rate `parse_file` accepts, as many as the real puzzle has.
+/// Part 2 has to consider every set of them, so its work doubles with each one more: at
+/// this size it takes seconds even when every valve is a step from every other, and a few
+/// more make that minutes.
+pub const MAX_RATED: usize = 15;
+
+/// The valve both parts start from
+pub const START: &str = "AA";
+
+pub fn parse_file(text: &str) -> Result<ValveGraph<'_>, ParseError> {
     let regex = Regex::new(
-        r"Valve (?P<valve>([A-Z])+) has flow rate=(?P<rate>(\d)+); tunnels? leads? to valves? (?P<out>[A-Z, ]+)",
+        r"^Valve (?P<valve>[A-Z]+) has flow rate=(?P<rate>\d+); tunnels? leads? to valves? (?P<out>[A-Z]+(, [A-Z]+)*)$",
     ).unwrap();
 
     let mut graph = ValveGraph::default();
-    text.lines().for_each(|l| {
-        let captures = regex.captures(l).unwrap();
+    let mut ids = Vec::new();
+    for (i, l) in text.lines().enumerate() {
+        let captures = regex.captures(l).ok_or_else(|| {
+            ParseError::new(
+                16,
+                i,
+                l,
+                "expected 'Valve XX has flow rate=N; tunnels lead to valves YY, ZZ'",
+            )
+        })?;
+        let id = captures.name("valve").unwrap().as_str();
         let outs = captures.name("out").unwrap().as_str().split(", ");
+        let rate = captures.name("rate").unwrap().as_str();
+        ids.push(id);
         graph.add_valve(Valve {
-            id: captures.name("valve").unwrap().as_str(),
+            id,
             outs: HashSet::from_iter(outs),
             paths: HashMap::new(),
-            is_open: false,
-            rate: captures.name("rate").unwrap().as_str().parse().unwrap(),
+            rate: rate
+                .parse()
+                .map_err(|_| ParseError::new(16, i, l, "flow rate too large").at(rate))?,
         });
-    });
+    }
 
-    graph
+    for (i, (l, id)) in text.lines().zip(ids).enumerate() {
+        if let Some(out) = graph.valves[id]
+            .outs
+            .iter()
+            .find(|out| !graph.valves.contains_key(*out))
+        {
+            return Err(ParseError::new(16, i, l, "tunnel leads to an unknown valve").at(out));
+        }
+    }
+    if !graph.valves.contains_key(START) {
+        return Err(ParseError::input(
+            16,
+            format!("no valve {} to start from", START),
+        ));
+    }
+    if graph.valves.values().filter(|v| v.rate > 0).count() > MAX_RATED {
+        return Err(ParseError::input(
+            16,
+            format!("more than {} valves have a flow rate", MAX_RATED),
+        ));
+    }
+
+    Ok(graph)
+}
+
+/// The `i`th valve's name: AA, AB, ... ZZ, then BAA and so on
+fn valve_name(mut i: usize) -> String {
+    let mut name = Vec::new();
+    while i > 0 || name.len() < 2 {
+        name.push((b'A' + (i % 26) as u8) as char);
+        i /= 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// `size` valves joined by random tunnels, about a quarter of them with a flow rate up to
+/// `MAX_RATED`. The tunnels always connect every valve to AA.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let count = size.max(2);
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let dig = |a: usize, b: usize, tunnels: &mut Vec<Vec<usize>>| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for valve in 1..count {
+        dig(rng.below(valve), valve, &mut tunnels);
+    }
+    for _ in 0..count / 2 {
+        dig(rng.below(count), rng.below(count), &mut tunnels);
+    }
+
+    let mut rated = 0;
+    let mut lines: Vec<String> = (0..count)
+        .map(|valve| {
+            let rate = match valve > 0 && rated < MAX_RATED && rng.one_in(4) {
+                true => {
+                    rated += 1;
+                    rng.range(1..=25)
+                }
+                false => 0,
+            };
+            let outs: Vec<String> = tunnels[valve].iter().map(|v| valve_name(*v)).collect();
+            let leads = match outs.len() {
+                1 => "tunnel leads to valve",
+                _ => "tunnels lead to valves",
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}",
+                valve_name(valve),
+                rate,
+                leads,
+                outs.join(", ")
+            )
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input<'a> = ValveGraph<'a>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(mut graph: Self::Input<'_>) -> Result<Answer, SolveError> {
+        graph.calculate_all_paths();
+        let route = graph.best_route(START, 30);
+        info!("open {:?}", route.valves);
+        Ok(route.pressure.into())
+    }
+
+    fn part2(mut graph: Self::Input<'_>) -> Result<Answer, SolveError> {
+        graph.calculate_all_paths();
+        let (pressure, you, elephant) = graph.best_pair(START, 26);
+        info!("you open {:?}", you.valves);
+        info!("the elephant opens {:?}", elephant.valves);
+        Ok(pressure.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use rayon::prelude::IntoParallelIterator;
-
     use super::*;
 
-    static TEST_STR: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let graph = parse_file(TEST_STR);
+        let graph = parse_file(TEST_STR).unwrap();
         assert_eq!(graph.valves.len(), 10);
         assert_eq!(graph.valves["GG"].outs.len(), 2);
         assert!(graph.valves["GG"].outs.contains("HH"));
     }
 
+    #[test]
+    fn test_parse_too_many_rated() {
+        // A ring of valves, all but AA with a flow rate
+        let ring = |count: usize| {
+            (0..count)
+                .map(|i| {
+                    format!(
+                        "Valve {} has flow rate={}; tunnel leads to valve {}",
+                        valve_name(i),
+                        (i > 0) as usize,
+                        valve_name((i + 1) % count)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        };
+        assert!(parse_file(&ring(MAX_RATED + 1)).is_ok());
+        let error = parse_file(&ring(MAX_RATED + 2)).unwrap_err();
+        assert_eq!(error.message, "more than 15 valves have a flow rate");
+        assert!(Day16::parse(&generate(300, &mut Rng::new(1))).is_ok());
+    }
+
+    #[test]
+    fn test_parse_without_start() {
+        let error = parse_file("Valve BB has flow rate=1; tunnel leads to valve BB").unwrap_err();
+        assert_eq!(error.message, "no valve AA to start from");
+    }
+
+    #[test]
+    fn test_best_pair_at_max_rated() {
+        // The most rated valves, each a step from every other: the most routes part 2 can
+        // be asked to weigh up, which still has to finish
+        let names: Vec<String> = (0..=MAX_RATED).map(valve_name).collect();
+        let text = (0..=MAX_RATED)
+            .map(|i| {
+                let outs: Vec<&str> = names
+                    .iter()
+                    .filter(|name| **name != names[i])
+                    .map(|name| name.as_str())
+                    .collect();
+                format!(
+                    "Valve {} has flow rate={}; tunnels lead to valves {}",
+                    names[i],
+                    i,
+                    outs.join(", ")
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let mut graph = parse_file(&text).unwrap();
+        graph.calculate_all_paths();
+        let (pressure, you, elephant) = graph.best_pair(START, 26);
+        assert_eq!(pressure, you.pressure + elephant.pressure);
+        assert_eq!(you.valves.len() + elephant.valves.len(), MAX_RATED);
+    }
+
     #[test]
     fn test_calculate_paths() {
-        let mut graph = parse_file(TEST_STR);
+        let mut graph = parse_file(TEST_STR).unwrap();
         graph.calculate_paths("AA");
         assert_eq!(
             graph.valves.get("AA").unwrap().paths.get("DD").unwrap(),
@@ -302,7 +441,7 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     fn test_calculate_all_paths() {
-        let mut graph = parse_file(TEST_STR);
+        let mut graph = parse_file(TEST_STR).unwrap();
         graph.calculate_all_paths();
         assert_eq!(
             graph.valves.get("AA").unwrap().paths.get("DD").unwrap(),
@@ -320,7 +459,7 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     fn test_get_expected_pressure() {
-        let mut graph = parse_file(TEST_STR);
+        let mut graph = parse_file(TEST_STR).unwrap();
         graph.calculate_all_paths();
         for v in graph.valves.keys() {
             println!(
@@ -344,137 +483,28 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
         // assert_eq!(graph.get_expected_pressure("AA", "CC", 30), 54);
     }
 
-    // #[test]
-    // fn test_get_best_pressure_path() {
-    //     let mut graph = parse_file(TEST_STR);
-    //     graph.calculate_all_paths();
-    //     assert_eq!(graph.get_best_pressure_path("AA", 30), vec!["DD"]);
-    // }
-
     #[test]
-    fn test_execute() {
-        let mut graph = parse_file(TEST_STR);
+    fn test_best_route() {
+        let mut graph = parse_file(TEST_STR).unwrap();
         graph.calculate_all_paths();
-        assert_eq!(
-            graph
-                .clone()
-                .execute(&vec!["DD", "BB", "JJ", "HH", "EE", "CC"]),
-            1651
-        );
-        let permutations = graph.calculate_permutations();
-        // println!("{:?}", permutations);
-        let output: usize = permutations
-            .into_par_iter()
-            .fold(
-                || 0,
-                |mut acc, p| {
-                    let result = graph.clone().execute(&p);
-                    acc = result.max(acc);
-                    acc
-                },
-            )
-            .max()
-            .unwrap();
-        // print!("{:?}", outputs);
-        assert_eq!(output, 1651);
+        let route = graph.best_route("AA", 30);
+        assert_eq!(route.pressure, 1651);
+        assert_eq!(route.valves, vec!["DD", "BB", "JJ", "HH", "EE", "CC"]);
+        // Starting elsewhere, or with less time, changes the best order
+        assert_eq!(graph.best_route("JJ", 30).valves[0], "JJ");
+        assert_eq!(graph.best_route("AA", 3).valves, vec!["DD"]);
+        assert_eq!(graph.best_route("AA", 1), Route::default());
     }
-}
 
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let mut graph = parse_file(&contents);
-    graph.calculate_all_paths();
-    let non_zero_valves: Vec<&str> = graph
-        .valves
-        .keys()
-        .filter(|k| graph.valves.get(*k).unwrap().rate > 0)
-        .map(|k| *k)
-        .collect();
-    // println!("{:?}", non_zero_valves);
-    // let start = vec!["OM", "RO", "SP"];
-
-    //["OM", "VR", "SP", "RO", "KZ", "DI", "SO"] 1356
-
-    let permutations = non_zero_valves
-        .iter()
-        .filter(|v| !vec!["OM", "VR", "SP", "RO", "KZ", "DI", "SO"].contains(v))
-        .permutations(7);
-    let output: usize = permutations
-        .par_bridge()
-        .fold(
-            || 0,
-            |mut acc, p| {
-                let result = graph
-                    .clone()
-                    .execute(&p.iter().map(|s| **s).collect::<Vec<&str>>());
-                if result.max(acc) > acc {
-                    println!("{} {:?}", result, p)
-                }
-                acc = result.max(acc);
-                acc
-            },
-        )
-        .max()
-        .unwrap();
-
-    println!("{}", output);
-    // let mut highest_result = 0;
-
-    // for i in 1..=5 {
-    //     // for s in non_zero_valves.iter() {
-    //     // let start = vec!["OM", "VR", "RO"];
-    //     // let start = vec!["OM", "VR", "RO", "SP", "KZ", "DI", "SO"];
-    //     // let start = vec!["OM", "RO", "SP", "KZ", "DI", "SO", "SC"];
-    //     // let start = vec!["RO", "SP", "KZ", "DI", "SO", "OM"];
-    //     // let start = vec!["RO", "SP", "KZ", "DI", "SO", "SC", "PW", "IR", "OM", "RI"];
-    //     let start = vec!["SP", "KZ"];
-    //     // let start = vec![];
-    //     // let start = vec![*s];
-    //     let permutations = non_zero_valves
-    //         .iter()
-    //         .filter(|v| !start.contains(*v))
-    //         .permutations(i);
-
-    //     let bar = ProgressBar::new(360360 as u64);
-    //     for p in permutations {
-    //         bar.inc(1);
-    //         let mut end = p.iter().map(|s| **s).collect::<Vec<&str>>();
-
-    //         {
-    //             let mut path = start.clone();
-    //             path.append(&mut end.clone());
-    //             let result = graph.clone().execute(&path);
-
-    //             if result > highest_result {
-    //                 println!("{result}, {:?}", path);
-    //                 highest_result = result;
-    //             }
-    //         }
-    //         {
-    //             let mut path = end.clone();
-    //             path.append(&mut start.clone());
-    //             let result = graph.clone().execute(&path);
-
-    //             if result > highest_result {
-    //                 println!("{result}, {:?}", path);
-    //                 highest_result = result;
-    //             }
-    //         }
-    //         // result
-    //     }
-    //     bar.finish();
-    // }
-    // }
-    // println!("{}", outputs.max().unwrap());
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    // let contents = read_file(module_path!());
-    // let (mut grid, sensors) = parse_file(&contents);
-    // println!(
-    //     "{}",
-    //     find_tuning(&mut grid, &sensors, 4_000_000, 4_000_000).unwrap()
-    // );
+    #[test]
+    fn test_best_pair() {
+        let mut graph = parse_file(TEST_STR).unwrap();
+        graph.calculate_all_paths();
+        let (pressure, you, elephant) = graph.best_pair("AA", 26);
+        assert_eq!(pressure, 1707);
+        assert_eq!(you.pressure + elephant.pressure, 1707);
+        let mut routes = [you.valves, elephant.valves];
+        routes.sort();
+        assert_eq!(routes, [vec!["DD", "HH", "EE"], vec!["JJ", "BB", "CC"]]);
+    }
 }
@@ -1,26 +1,32 @@
-use super::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Debug, Clone, PartialEq)]
-enum Play {
+pub enum Play {
     Rock = 0,
     Paper = 1,
     Scissors = 2,
 }
-impl From<&str> for Play {
-    fn from(f: &str) -> Self {
+impl TryFrom<&str> for Play {
+    type Error = String;
+
+    fn try_from(f: &str) -> Result<Self, Self::Error> {
         match f {
-            "A" => Play::Rock,
-            "X" => Play::Rock,
-            "B" => Play::Paper,
-            "Y" => Play::Paper,
-            "C" => Play::Scissors,
-            "Z" => Play::Scissors,
-            _ => panic!("Unknown Play '{}'", f),
+            "A" => Ok(Play::Rock),
+            "X" => Ok(Play::Rock),
+            "B" => Ok(Play::Paper),
+            "Y" => Ok(Play::Paper),
+            "C" => Ok(Play::Scissors),
+            "Z" => Ok(Play::Scissors),
+            _ => Err(format!("unknown play '{}'", f)),
         }
     }
 }
 impl Play {
-    fn value(&self) -> u8 {
+    pub fn value(&self) -> u8 {
         match *self {
             Play::Rock => 1,
             Play::Paper => 2,
@@ -30,44 +36,38 @@ impl Play {
 
     /// The rules of rock-paper-scissors
     /// ```
+    /// # use advent_of_code_2022::day2::Play;
     /// assert!(Play::Paper.beats(&Play::Rock));
     /// ```
-    fn beats(&self, other: &Play) -> bool {
+    pub fn beats(&self, other: &Play) -> bool {
         match *self {
-            Play::Rock => match other {
-                Play::Scissors => true,
-                _ => false,
-            },
-            Play::Paper => match other {
-                Play::Rock => true,
-                _ => false,
-            },
-            Play::Scissors => match other {
-                Play::Paper => true,
-                _ => false,
-            },
+            Play::Rock => matches!(other, Play::Scissors),
+            Play::Paper => matches!(other, Play::Rock),
+            Play::Scissors => matches!(other, Play::Paper),
         }
     }
 }
 
 #[derive(Debug, Clone)]
-enum Outcome {
+pub enum Outcome {
     Win,
     Lose,
     Draw,
 }
-impl From<&str> for Outcome {
-    fn from(f: &str) -> Self {
+impl TryFrom<&str> for Outcome {
+    type Error = String;
+
+    fn try_from(f: &str) -> Result<Self, Self::Error> {
         match f {
-            "X" => Outcome::Lose,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            _ => panic!("Unknown Outcome '{}'", f),
+            "X" => Ok(Outcome::Lose),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            _ => Err(format!("unknown outcome '{}'", f)),
         }
     }
 }
 impl Outcome {
-    fn value(&self) -> u8 {
+    pub fn value(&self) -> u8 {
         match *self {
             Outcome::Win => 6,
             Outcome::Draw => 3,
@@ -77,17 +77,17 @@ impl Outcome {
 }
 
 #[derive(Debug, Clone)]
-struct Round {
-    opponent: Play,
-    me: Play,
+pub struct Round {
+    pub opponent: Play,
+    pub me: Play,
 }
 
 impl Round {
-    fn score(&self) -> u8 {
+    pub fn score(&self) -> u8 {
         self.me.value() + self.outcome().value()
     }
 
-    fn outcome(&self) -> Outcome {
+    pub fn outcome(&self) -> Outcome {
         if self.me == self.opponent {
             return Outcome::Draw;
         }
@@ -98,28 +98,38 @@ impl Round {
     }
 }
 
-fn parse_file(text: &str) -> Vec<Round> {
-    let round_lines = text.split("\n");
-    let rounds = round_lines
-        .map(|line| {
-            let plays: Vec<&str> = line.split(" ").collect();
-            Round {
-                opponent: plays.first().unwrap().to_owned().into(),
-                me: plays.last().unwrap().to_owned().into(),
-            }
+/// Parses each line's two columns with `first` and `second`, pointing errors at the bad column
+pub fn parse_columns<'a, A, B>(
+    text: &'a str,
+    first: impl Fn(&'a str) -> Result<A, String>,
+    second: impl Fn(&'a str) -> Result<B, String>,
+) -> Result<Vec<(A, B)>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (a, b) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::new(2, i, line, "expected two columns"))?;
+            let a = first(a).map_err(|e| ParseError::new(2, i, line, e).at(a))?;
+            let b = second(b).map_err(|e| ParseError::new(2, i, line, e).at(b))?;
+            Ok((a, b))
         })
-        .collect();
+        .collect()
+}
 
-    rounds
+pub fn parse_file(text: &str) -> Result<Vec<Round>, ParseError> {
+    let rounds = parse_columns(text, Play::try_from, Play::try_from)?;
+    Ok(rounds
+        .into_iter()
+        .map(|(opponent, me)| Round { opponent, me })
+        .collect())
 }
 
-fn parse_file_2(text: &str) -> Vec<Round> {
-    let round_lines = text.split("\n");
-    let rounds = round_lines
-        .map(|line| {
-            let plays: Vec<&str> = line.split(" ").collect();
-            let opponent: Play = plays.first().unwrap().to_owned().into();
-            let outcome: Outcome = plays.last().unwrap().to_owned().into();
+pub fn parse_file_2(text: &str) -> Result<Vec<Round>, ParseError> {
+    let rounds = parse_columns(text, Play::try_from, Outcome::try_from)?;
+    Ok(rounds
+        .into_iter()
+        .map(|(opponent, outcome)| {
             let me: Play = match outcome {
                 Outcome::Win => match opponent {
                     Play::Rock => Play::Paper,
@@ -135,26 +145,55 @@ fn parse_file_2(text: &str) -> Vec<Round> {
             };
             Round { opponent, me }
         })
-        .collect();
-
-    rounds
+        .collect())
 }
 
-fn score_rounds(rounds: Vec<Round>) -> u32 {
+pub fn score_rounds(rounds: Vec<Round>) -> u32 {
     rounds.iter().fold(0, |acc, r| acc + u32::from(r.score()))
 }
 
+/// A random strategy guide of `size` rounds
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    // The second column means something different in each part, so read it both ways
+    type Input<'a> = (Vec<Round>, Vec<Round>);
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok((parse_file(text)?, parse_file_2(text)?))
+    }
+
+    fn part1((rounds, _): Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(score_rounds(rounds).into())
+    }
+
+    fn part2((_, rounds): Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(score_rounds(rounds).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "A Y
-B X
-C Z";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let rounds = parse_file(TEST_STR);
+        let rounds = parse_file(TEST_STR).unwrap();
         assert_eq!(rounds.len(), 3);
         assert_eq!(rounds.first().unwrap().opponent, Play::Rock);
         assert_eq!(rounds.first().unwrap().me, Play::Paper);
@@ -162,20 +201,13 @@ C Z";
 
     #[test]
     fn test_score_rounds() {
-        assert_eq!(score_rounds(parse_file(TEST_STR)), 15);
+        assert_eq!(score_rounds(parse_file(TEST_STR).unwrap()), 15);
     }
-}
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
 
-    println!("{}", score_rounds(parse_file(contents.as_str())))
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-
-    println!("{}", score_rounds(parse_file_2(contents.as_str())))
+    #[test]
+    fn test_parse_error() {
+        let error = parse_file("A Y\nB Q").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.message, "unknown play 'Q'");
+    }
 }
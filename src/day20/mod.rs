@@ -1,77 +1,144 @@
-use std::{collections::HashMap, ops::Deref};
-
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
+/// The numbers as a circular doubly linked list, with each number's neighbours kept by its
+/// place in the original order so repeated numbers don't get mixed up
 #[derive(Debug, Default)]
-struct Array {
-    left_links: HashMap<isize, isize>,
-    right_links: HashMap<isize, isize>,
+pub struct Array {
+    pub values: Vec<isize>,
+    pub left_links: Vec<usize>,
+    pub right_links: Vec<usize>,
 }
 
 impl Array {
-    fn insert(&mut self, value: isize, left: isize, right: isize) {
-        self.left_links.insert(value, left);
-        self.right_links.insert(value, right);
-    }
-
-    fn shift(&mut self, value: isize) {
-        let direction = value.signum();
-        for _ in 0..value.abs() {
-            if direction == 1 {
-                let right = *self.right_links.get(&value).unwrap();
-                // println!("right of {value} is {right}");
-                self.left_links
-                    .insert(right, *self.left_links.get(&value).unwrap());
-                self.right_links
-                    .insert(*self.left_links.get(&value).unwrap(), right);
-                self.left_links.insert(value, right);
-                self.right_links
-                    .insert(value, *self.right_links.get(&right).unwrap());
-                self.left_links
-                    .insert(*self.right_links.get(&right).unwrap(), value);
-                self.right_links.insert(right, value);
-            } else if direction == -1 {
-                let left = *self.left_links.get(&value).unwrap();
-                self.right_links
-                    .insert(left, *self.right_links.get(&value).unwrap());
-                self.left_links
-                    .insert(*self.right_links.get(&value).unwrap(), left);
-                self.right_links.insert(value, left);
-                self.left_links
-                    .insert(value, *self.left_links.get(&left).unwrap());
-                self.right_links
-                    .insert(*self.left_links.get(&left).unwrap(), value);
-                self.left_links.insert(left, value);
+    pub fn new(values: Vec<isize>) -> Array {
+        let n = values.len();
+        Array {
+            left_links: (0..n).map(|i| (i + n - 1) % n).collect(),
+            right_links: (0..n).map(|i| (i + 1) % n).collect(),
+            values,
+        }
+    }
+
+    /// Moves the number that started at `index` as many places as its value, wrapping round
+    pub fn shift(&mut self, index: usize) {
+        // With the number taken out there are one fewer places to move through
+        let places = self.values.len() - 1;
+        let steps = self.values[index].rem_euclid(places as isize) as usize;
+        if steps == 0 {
+            return;
+        }
+        let (left, right) = (self.left_links[index], self.right_links[index]);
+        self.right_links[left] = right;
+        self.left_links[right] = left;
+
+        // Go whichever way round is shorter
+        let mut after = left;
+        if steps <= places / 2 {
+            for _ in 0..steps {
+                after = self.right_links[after];
+            }
+        } else {
+            for _ in steps..places {
+                after = self.left_links[after];
             }
         }
+        let before = self.right_links[after];
+        self.right_links[after] = index;
+        self.left_links[index] = after;
+        self.right_links[index] = before;
+        self.left_links[before] = index;
     }
 
-    fn get_offset(&self, value: isize, offset: usize) -> isize {
-        let mut result = value;
-        for _ in 0..offset {
-            result = *self.right_links.get(&result).unwrap();
+    /// The number `offset` places after the first `value`
+    pub fn get_offset(&self, value: isize, offset: usize) -> isize {
+        let mut index = self.values.iter().position(|v| *v == value).unwrap();
+        for _ in 0..offset % self.values.len() {
+            index = self.right_links[index];
         }
-        result
+        self.values[index]
+    }
+
+    /// The sum of the numbers 1000, 2000 and 3000 places after 0
+    pub fn grove_coordinates(&self) -> isize {
+        [1000, 2000, 3000]
+            .iter()
+            .map(|offset| self.get_offset(0, *offset))
+            .sum()
     }
 }
 
-fn parse_file(text: &str) -> (Array, Vec<isize>) {
-    let mut array = Array {
-        ..Default::default()
-    };
+pub fn parse_file(text: &str) -> Result<Vec<isize>, ParseError> {
+    let vec: Vec<isize> = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            l.parse::<isize>()
+                .map_err(|_| ParseError::new(20, i, l, "expected a number"))
+        })
+        .collect::<Result<_, _>>()?;
+    if vec.len() < 2 {
+        return Err(ParseError::input(20, "need at least two numbers to mix"));
+    }
+    if !vec.contains(&0) {
+        return Err(ParseError::input(
+            20,
+            "no 0 to count grove coordinates from",
+        ));
+    }
+    Ok(vec)
+}
+
+/// The key part 2 multiplies every number by before mixing
+pub const DECRYPTION_KEY: isize = 811589153;
 
-    let vec: Vec<isize> = text.lines().map(|l| l.parse::<isize>().unwrap()).collect();
-    array.insert(vec[0], *vec.last().unwrap(), vec[1]);
-    array.insert(*vec.last().unwrap(), vec[vec.len() - 2], vec[0]);
-    for i in 1..vec.len() - 1 {
-        array.insert(vec[i], vec[i - 1], vec[i + 1]);
+/// Multiplies every number by `key`, then mixes them `rounds` times, always moving them in
+/// their original order
+pub fn mix(numbers: &[isize], rounds: usize, key: isize) -> Array {
+    let mut array = Array::new(numbers.iter().map(|n| n * key).collect());
+    for _ in 0..rounds {
+        for index in 0..numbers.len() {
+            array.shift(index);
+        }
     }
-    (array, vec)
+    array
 }
 
-fn mix(array: &mut Array, vec: &Vec<isize>) {
-    for v in vec.iter() {
-        array.shift(*v);
-        // println!("{:?}", array);
+/// A random list of `size` numbers to mix, with a single 0 and plenty of repeats
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut numbers: Vec<isize> = (1..size.max(2))
+        .map(|_| loop {
+            let n = rng.range_signed(-10_000..=10_000);
+            if n != 0 {
+                break n;
+            }
+        })
+        .collect();
+    numbers.insert(rng.range(0..=numbers.len()), 0);
+    numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input<'a> = Vec<isize>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(numbers: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(mix(&numbers, 1, 1).grove_coordinates().into())
+    }
+
+    fn part2(numbers: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(mix(&numbers, 10, DECRYPTION_KEY).grove_coordinates().into())
     }
 }
 
@@ -79,61 +146,78 @@ fn mix(array: &mut Array, vec: &Vec<isize>) {
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "1
-2
--3
-3
--2
-0
-4";
+    static TEST_STR: &str = include_str!("example1.txt");
+
+    /// Mixes by moving numbers about in a plain list, slowly but obviously right
+    fn mix_naively(numbers: &[isize], rounds: usize, key: isize) -> Vec<isize> {
+        let mut list: Vec<(usize, isize)> = numbers.iter().map(|n| n * key).enumerate().collect();
+        for _ in 0..rounds {
+            for index in 0..numbers.len() {
+                let from = list.iter().position(|(i, _)| *i == index).unwrap();
+                let number = list.remove(from);
+                let to = (from as isize + number.1).rem_euclid(list.len() as isize);
+                list.insert(to as usize, number);
+            }
+        }
+        list.into_iter().map(|(_, n)| n).collect()
+    }
+
+    /// The numbers in order, starting from 0
+    fn from_zero(array: &Array) -> Vec<isize> {
+        (0..array.values.len())
+            .map(|offset| array.get_offset(0, offset))
+            .collect()
+    }
 
     #[test]
     fn test_parse_file() {
-        let (array, vec) = parse_file(TEST_STR);
-        assert_eq!(*array.left_links.get(&-2).unwrap(), 3);
-        assert_eq!(vec[2], -3);
+        let vec = parse_file(TEST_STR).unwrap();
+        assert_eq!(vec, vec![1, 2, -3, 3, -2, 0, 4]);
     }
 
     #[test]
     fn test_mix() {
-        let (mut array, vec) = parse_file(TEST_STR);
-        mix(&mut array, &vec);
-        assert_eq!(*array.left_links.get(&4).unwrap(), -3);
-        assert_eq!(*array.right_links.get(&0).unwrap(), 3);
+        let numbers = parse_file(TEST_STR).unwrap();
+        let array = mix(&numbers, 1, 1);
+        assert_eq!(from_zero(&array), vec![0, 3, -2, 1, 2, -3, 4]);
+        assert_eq!(array.grove_coordinates(), 3);
+
+        let array = mix(&numbers, 10, DECRYPTION_KEY);
+        assert_eq!(
+            from_zero(&array),
+            vec![
+                0,
+                -2434767459,
+                1623178306,
+                3246356612,
+                -1623178306,
+                2434767459,
+                811589153
+            ]
+        );
+        assert_eq!(array.grove_coordinates(), 1623178306);
+    }
+
+    #[test]
+    fn test_mix_repeated_numbers() {
+        let numbers = parse_file(&generate(40, &mut Rng::new(3))).unwrap();
+        for (rounds, key) in [(1, 1), (3, 7), (2, DECRYPTION_KEY)] {
+            let mut expected = mix_naively(&numbers, rounds, key);
+            let zero = expected.iter().position(|n| *n == 0).unwrap();
+            expected.rotate_left(zero);
+            assert_eq!(from_zero(&mix(&numbers, rounds, key)), expected);
+        }
     }
 
     #[test]
     fn test_get_offset() {
-        let (mut array, vec) = parse_file(TEST_STR);
+        let numbers = parse_file(TEST_STR).unwrap();
+        let array = Array::new(numbers.clone());
         assert_eq!(array.get_offset(0, 1), 4);
         assert_eq!(array.get_offset(0, 3), 2);
-        mix(&mut array, &vec);
+        let array = mix(&numbers, 1, 1);
         assert_eq!(array.get_offset(0, 1000), 4);
         assert_eq!(array.get_offset(0, 2000), -3);
         assert_eq!(array.get_offset(0, 3000), 2);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let (mut array, vec) = parse_file(&contents);
-    mix(&mut array, &vec);
-    println!(
-        "{}",
-        array.get_offset(0, 1000) + array.get_offset(0, 2000) + array.get_offset(0, 3000)
-    );
-    // assert_eq!(array.get_offset(0, 1000), 4);
-    // assert_eq!(array.get_offset(0, 2000), -3);
-    // assert_eq!(array.get_offset(0, 3000), 2);}
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    // let (mut grid, sensors) = parse_file(&contents);
-    // println!(
-    //     "{}",
-    //     find_tuning(&mut grid, &sensors, 4_000_000, 4_000_000).unwrap()
-    // );
-}
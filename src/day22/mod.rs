@@ -1,327 +1,464 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    geometry::{Facing, Point},
+    grid::Grid,
+    parse::ParseError,
+    registry::Part,
+    solution::{Answer, Solution, SolveError},
+    visualize::{Frame, Recorder, Visualize},
+};
 
 #[derive(Debug, Clone, PartialEq)]
-enum GridState {
+pub enum GridState {
     Void,
     Path,
     Wall,
 }
+
 #[derive(Debug, Clone, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Move(usize),
     Right,
     Left,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum Facing {
-    North,
-    South,
-    East,
-    West,
+/// The password's score for each facing
+fn facing_value(facing: &Facing) -> usize {
+    match facing {
+        Facing::Right => 0,
+        Facing::Down => 1,
+        Facing::Left => 2,
+        Facing::Up => 3,
+    }
 }
 
-impl Facing {
-    fn value(&self) -> usize {
-        match self {
-            Facing::North => 3,
-            Facing::South => 1,
-            Facing::East => 0,
-            Facing::West => 2,
-        }
-    }
-    fn turn(&self, direction: &Instruction) -> Facing {
-        match direction {
-            Instruction::Move(_) => self.clone(),
-            Instruction::Right => match self {
-                Facing::North => Facing::East,
-                Facing::South => Facing::West,
-                Facing::East => Facing::South,
-                Facing::West => Facing::North,
-            },
-            Instruction::Left => match self {
-                Facing::North => Facing::West,
-                Facing::South => Facing::East,
-                Facing::East => Facing::North,
-                Facing::West => Facing::South,
-            },
-        }
+fn turn(facing: &Facing, instruction: &Instruction) -> Facing {
+    match instruction {
+        Instruction::Move(_) => *facing,
+        Instruction::Right => facing.turn_right(),
+        Instruction::Left => facing.turn_left(),
     }
 }
 
-type Grid = Vec<Vec<GridState>>;
+pub type Board = Grid<GridState>;
 
-fn parse_file(text: &str) -> (Grid, Vec<Instruction>) {
+pub fn parse_file(text: &str) -> Result<(Board, Vec<Instruction>), ParseError> {
     let mut lines: Vec<&str> = text.lines().collect();
-    let instruction_line = lines.pop().unwrap();
-    lines.pop();
+    let instruction_index = lines.len().saturating_sub(1);
+    let instruction_line = lines
+        .pop()
+        .ok_or_else(|| ParseError::input(22, "empty input"))?;
+    if lines.pop().filter(|l| l.is_empty()).is_none() {
+        return Err(ParseError::input(
+            22,
+            "expected a blank line before the path",
+        ));
+    }
     let mut instructions = Vec::new();
     let mut char_buffer: Vec<char> = Vec::new();
-    for c in instruction_line.chars() {
-        if c == 'L' || c == 'R' {
-            instructions.push(Instruction::Move(
-                char_buffer
-                    .iter()
-                    .collect::<String>()
-                    .parse::<usize>()
-                    .unwrap(),
-            ));
-            char_buffer.truncate(0);
-        }
-        match c {
-            'L' => instructions.push(Instruction::Left),
-            'R' => instructions.push(Instruction::Right),
-            _ => char_buffer.push(c),
-        }
-    }
-    instructions.push(Instruction::Move(
-        char_buffer
+    let flush = |column: usize, char_buffer: &mut Vec<char>| {
+        let steps = char_buffer
             .iter()
             .collect::<String>()
             .parse::<usize>()
-            .unwrap(),
-    ));
-
-    let mut grid: Grid = Vec::new();
-    lines.iter().for_each(|l| {
-        let chars = l.chars();
-        let mut row = Vec::new();
-        for c in chars {
-            row.push(match c {
-                ' ' => GridState::Void,
-                '.' => GridState::Path,
-                '#' => GridState::Wall,
-                _ => panic!("Unknown char {}", c),
-            });
+            .map_err(|_| {
+                ParseError::new(
+                    22,
+                    instruction_index,
+                    instruction_line,
+                    "expected a number of steps",
+                )
+                .at_column(column - char_buffer.len())
+            })?;
+        char_buffer.truncate(0);
+        Ok(Instruction::Move(steps))
+    };
+    for (column, c) in instruction_line.chars().enumerate() {
+        match c {
+            'L' | 'R' => {
+                instructions.push(flush(column, &mut char_buffer)?);
+                instructions.push(if c == 'L' {
+                    Instruction::Left
+                } else {
+                    Instruction::Right
+                });
+            }
+            _ => char_buffer.push(c),
         }
+    }
+    instructions.push(flush(instruction_line.chars().count(), &mut char_buffer)?);
 
-        grid.push(row);
-    });
+    let grid = Grid::parse_padded(22, &lines.join("\n"), GridState::Void, |c| match c {
+        ' ' => Some(GridState::Void),
+        '.' => Some(GridState::Path),
+        '#' => Some(GridState::Wall),
+        _ => None,
+    })?;
 
-    (grid, instructions)
+    Ok((grid, instructions))
 }
 
-fn get_next_position_map(
-    grid: &Grid,
-    position: &(usize, usize),
-    facing: &Facing,
-) -> (usize, usize) {
-    match facing {
-        Facing::North => {
-            if position.0 == 0
-                || position.1 > grid[position.0 - 1].len() - 1
-                || grid[position.0 - 1][position.1] == GridState::Void
-            {
-                let valid_position = grid
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .find(|(_, col)| {
-                        position.1 < col.len() - 1 && col[position.1] != GridState::Void
-                    })
-                    .unwrap();
-                (grid.len() - 1 - valid_position.0, position.1)
-            } else {
-                (position.0 - 1, position.1)
-            }
-        }
-        Facing::South => {
-            if position.0 == grid.len() - 1
-                || position.1 > grid[position.0 + 1].len() - 1
-                || grid[position.0 + 1][position.1] == GridState::Void
-            {
-                let valid_position = grid
-                    .iter()
-                    .enumerate()
-                    .find(|(_, col)| {
-                        position.1 < col.len() - 1 && col[position.1] != GridState::Void
-                    })
-                    .unwrap();
-                (valid_position.0, position.1)
-            } else {
-                (position.0 + 1, position.1)
-            }
-        }
-        Facing::East => {
-            if position.1 == grid[position.0].len() - 1
-                || grid[position.0][position.1 + 1] == GridState::Void
-            {
-                let valid_position = grid[position.0]
-                    .iter()
-                    .enumerate()
-                    .find(|(i, col)| **col != GridState::Void)
-                    .unwrap();
-                (position.0, valid_position.0)
-            } else {
-                (position.0, position.1 + 1)
-            }
+/// The next position, wrapping round to the far side of the row or column
+pub fn get_next_position_map(grid: &Board, position: &Point, facing: &Facing) -> Point {
+    let on_map = |p: Point| grid.at(p).is_some_and(|t| *t != GridState::Void);
+    let step = facing.vector();
+    if on_map(*position + step) {
+        return *position + step;
+    }
+    let mut wrapped = *position;
+    while on_map(wrapped - step) {
+        wrapped -= step;
+    }
+    wrapped
+}
+
+/// A direction or position in space, with positions doubled so tile centres are whole
+type Vector3 = [isize; 3];
+
+fn neg(v: Vector3) -> Vector3 {
+    v.map(|c| -c)
+}
+
+fn add(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(v: Vector3, n: isize) -> Vector3 {
+    v.map(|c| c * n)
+}
+
+fn dot(a: Vector3, b: Vector3) -> isize {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Where a face of the net ends up once folded: the ways its right and down edges and its
+/// outside point
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Face {
+    right: Vector3,
+    down: Vector3,
+    normal: Vector3,
+}
+
+impl Face {
+    /// The neighbouring face of the net in `facing`'s direction, folded down over the edge
+    fn fold(&self, facing: &Facing) -> Face {
+        let Face {
+            right,
+            down,
+            normal,
+        } = *self;
+        match facing {
+            Facing::Right => Face {
+                right: neg(normal),
+                down,
+                normal: right,
+            },
+            Facing::Left => Face {
+                right: normal,
+                down,
+                normal: neg(right),
+            },
+            Facing::Down => Face {
+                right,
+                down: neg(normal),
+                normal: down,
+            },
+            Facing::Up => Face {
+                right,
+                down: normal,
+                normal: neg(down),
+            },
         }
-        Facing::West => {
-            if position.1 == 0 || grid[position.0][position.1 - 1] == GridState::Void {
-                let valid_position = grid[position.0]
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .find(|(i, col)| **col != GridState::Void)
-                    .unwrap();
-                (position.0, grid[position.0].len() - 1 - valid_position.0)
-            } else {
-                (position.0, position.1 - 1)
-            }
+    }
+
+    /// The way through space that facing this way on the face points
+    fn direction(&self, facing: &Facing) -> Vector3 {
+        match facing {
+            Facing::Right => self.right,
+            Facing::Down => self.down,
+            Facing::Left => neg(self.right),
+            Facing::Up => neg(self.down),
         }
     }
 }
 
-fn get_next_position_cube(
-    grid: &Grid,
-    position: &(usize, usize),
-    facing: &Facing,
-) -> ((usize, usize), Facing) {
-    let cube_size = grid
-        .iter()
-        .enumerate()
-        .find(|(_, col)| col[0] != GridState::Void)
-        .unwrap()
-        .0;
+/// The map folded up into a cube, whichever of the nets it is drawn as
+#[derive(Debug)]
+pub struct Cube {
+    size: isize,
+    /// Each face by its position in the net, counted in faces
+    faces: HashMap<Point, Face>,
+}
 
-    match facing {
-        Facing::North => {
-            if position.0 == 0 {
-                //1 -> 5
-                ((grid.len() - 1, position.1), *facing)
-            } else if position.1 > grid[position.0 - 1].len() - 1 {
-                //6 -> 4
-                (
-                    (
-                        cube_size * 2 - (position.1 - cube_size * 3),
-                        cube_size * 3 - 1,
-                    ),
-                    Facing::West,
-                )
-            } else if grid[position.0 - 1][position.1] == GridState::Void {
-                if position.1 < cube_size {
-                    //3 -> 1
-                    todo!()
-                } else {
-                    //2 -> 1
-                    todo!()
-                }
-            } else {
-                ((position.0 - 1, position.1), facing)
-            }
+impl Cube {
+    /// Works out the face size from the number of tiles and folds the faces up from the first
+    /// one. Fails if the map is not a net of a cube.
+    pub fn fold(grid: &Board) -> Option<Cube> {
+        let tiles = grid.iter().filter(|(_, t)| **t != GridState::Void).count();
+        let size = (1..).find(|n| 6 * n * n >= tiles)?;
+        if 6 * size * size != tiles {
+            return None;
         }
-        Facing::South => {
-            if position.0 == grid.len() - 1
-                || position.1 > grid[position.0 + 1].len() - 1
-                || grid[position.0 + 1][position.1] == GridState::Void
-            {
-                let valid_position = grid
-                    .iter()
-                    .enumerate()
-                    .find(|(_, col)| {
-                        position.1 < col.len() - 1 && col[position.1] != GridState::Void
-                    })
-                    .unwrap();
-                (valid_position.0, position.1)
-            } else {
-                (position.0 + 1, position.1)
+        let on_map = |cell: Point| {
+            grid.at(cell * size as isize)
+                .is_some_and(|t| *t != GridState::Void)
+        };
+
+        let first = (0..grid.height() / size)
+            .flat_map(|y| (0..grid.width() / size).map(move |x| Point::from((x, y))))
+            .find(|cell| on_map(*cell))?;
+        let mut faces = HashMap::from([(
+            first,
+            Face {
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+                normal: [0, 0, -1],
+            },
+        )]);
+        let mut unvisited = vec![first];
+        while let Some(cell) = unvisited.pop() {
+            let face = faces[&cell];
+            for facing in Facing::ALL {
+                let neighbour = cell + facing.vector();
+                if on_map(neighbour) && !faces.contains_key(&neighbour) {
+                    faces.insert(neighbour, face.fold(&facing));
+                    unvisited.push(neighbour);
+                }
             }
         }
-        Facing::East => {
-            if position.1 == grid[position.0].len() - 1
-                || grid[position.0][position.1 + 1] == GridState::Void
-            {
-                let valid_position = grid[position.0]
-                    .iter()
-                    .enumerate()
-                    .find(|(i, col)| **col != GridState::Void)
-                    .unwrap();
-                (position.0, valid_position.0)
-            } else {
-                (position.0, position.1 + 1)
-            }
+
+        let normals: HashSet<Vector3> = faces.values().map(|f| f.normal).collect();
+        if faces.len() != 6 || normals.len() != 6 {
+            return None;
         }
-        Facing::West => {
-            if position.1 == 0 || grid[position.0][position.1 - 1] == GridState::Void {
-                let valid_position = grid[position.0]
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .find(|(i, col)| **col != GridState::Void)
-                    .unwrap();
-                (position.0, grid[position.0].len() - 1 - valid_position.0)
-            } else {
-                (position.0, position.1 - 1)
-            }
+        Some(Cube {
+            size: size as isize,
+            faces,
+        })
+    }
+
+    /// The next position and facing, carrying on round the cube when walking off a face's edge
+    pub fn next_position(
+        &self,
+        grid: &Board,
+        position: &Point,
+        facing: &Facing,
+    ) -> (Point, Facing) {
+        let next = *position + facing.vector();
+        if grid.at(next).is_some_and(|t| *t != GridState::Void) {
+            return (next, *facing);
         }
+
+        let n = self.size;
+        let cell = Point::new(position.x / n, position.y / n);
+        let face = self.faces[&cell];
+        let local = *position - cell * n;
+        let centre = add(
+            scale(face.normal, n),
+            add(
+                scale(face.right, 2 * local.x + 1 - n),
+                scale(face.down, 2 * local.y + 1 - n),
+            ),
+        );
+        // Over the edge the way we were going, then one tile down the side of the next face
+        let travel = face.direction(facing);
+        let centre = add(add(centre, travel), neg(face.normal));
+
+        let (target_cell, target) = self.faces.iter().find(|(_, f)| f.normal == travel).unwrap();
+        let facing = Facing::ALL
+            .into_iter()
+            .find(|f| target.direction(f) == neg(face.normal))
+            .unwrap();
+        let local = Point::new(
+            (dot(centre, target.right) + n - 1) / 2,
+            (dot(centre, target.down) + n - 1) / 2,
+        );
+        (*target_cell * n + local, facing)
     }
 }
 
-fn get_next_position(
-    is_cube: bool,
-    grid: &Grid,
-    position: &(usize, usize),
-    facing: &Facing,
-) -> (usize, usize) {
-    if is_cube {
-        get_next_position_cube(grid, position, facing)
-    } else {
-        get_next_position_map(grid, position, facing)
+/// The arrow the puzzle uses to mark a step taken while facing this way
+fn facing_symbol(facing: &Facing) -> char {
+    match facing {
+        Facing::Right => '>',
+        Facing::Down => 'v',
+        Facing::Left => '<',
+        Facing::Up => '^',
     }
 }
 
-fn execute(
-    is_cube: bool,
-    grid: &Grid,
-    instructions: &Vec<Instruction>,
-) -> ((usize, usize), Facing) {
-    let mut facing = Facing::East;
-    let mut position = (0, 0);
+fn draw_board(grid: &Board) -> Frame {
+    grid.map(|state| match state {
+        GridState::Void => ' ',
+        GridState::Path => '.',
+        GridState::Wall => '#',
+    })
+}
+
+/// Follows the path from the leftmost open tile of the top row, taking each step with
+/// `next_position`, recording the trail walked so far after every instruction. `None` if a
+/// step lands off the map.
+pub fn execute(
+    grid: &Board,
+    instructions: &[Instruction],
+    next_position: impl Fn(&Point, &Facing) -> (Point, Facing),
+    recorder: &mut dyn Recorder,
+) -> Option<(Point, Facing)> {
+    let mut facing = Facing::Right;
+    let mut position: Point = grid
+        .position(|t| *t == GridState::Path)
+        .map_or(Point::ORIGIN, Point::from);
+    let mut trail = draw_board(grid);
     for instruction in instructions.iter() {
         match instruction {
             Instruction::Move(d) => {
                 for _ in 0..*d {
-                    let next_position = get_next_position(is_cube, grid, &position, &facing);
-                    match grid[next_position.0][next_position.1] {
-                        GridState::Void => panic!("Ran into void!"),
-                        GridState::Path => position = next_position,
+                    let (next, next_facing) = next_position(&position, &facing);
+                    match grid.at(next)? {
+                        GridState::Void => return None,
+                        GridState::Path => (position, facing) = (next, next_facing),
                         GridState::Wall => break,
                     }
+                    trail[position] = facing_symbol(&facing);
                 }
             }
-            _ => facing = facing.turn(instruction),
+            _ => facing = turn(&facing, instruction),
+        }
+        if recorder.is_recording() {
+            trail[position] = facing_symbol(&facing);
+            recorder.record(&trail);
+        }
+    }
+    Some((position, facing))
+}
+
+/// Follows the path on the flat map
+pub fn execute_map(
+    grid: &Board,
+    instructions: &[Instruction],
+    recorder: &mut dyn Recorder,
+) -> Option<(Point, Facing)> {
+    let next_position = |p: &Point, f: &Facing| (get_next_position_map(grid, p, f), *f);
+    execute(grid, instructions, next_position, recorder)
+}
+
+/// Follows the path on the map folded into `cube`
+pub fn execute_cube(
+    grid: &Board,
+    cube: &Cube,
+    instructions: &[Instruction],
+    recorder: &mut dyn Recorder,
+) -> Option<(Point, Facing)> {
+    let next_position = |p: &Point, f: &Facing| cube.next_position(grid, p, f);
+    execute(grid, instructions, next_position, recorder)
+}
+
+/// The final password: 1000 times the row, 4 times the column and the facing, counting from 1
+pub fn password(position: Point, facing: &Facing) -> isize {
+    (position.y + 1) * 1000 + (position.x + 1) * 4 + facing_value(facing) as isize
+}
+
+/// The eleven layouts of faces that fold into a cube, `#` for a face
+const NETS: [&[&str]; 11] = [
+    &["#...", "####", "#..."],
+    &["#...", "####", ".#.."],
+    &["#...", "####", "..#."],
+    &["#...", "####", "...#"],
+    &[".#..", "####", ".#.."],
+    &[".#..", "####", "..#."],
+    &["##..", ".###", ".#.."],
+    &["##..", ".###", "..#."],
+    &["##..", ".###", "...#"],
+    &["##..", ".##.", "..##"],
+    &["###..", "..###"],
+];
+
+/// A map laid out as `net` with `face` tiles along each edge and walls scattered about
+fn draw_net(net: &[&str], face: usize, rng: &mut Rng) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (row, faces) in net.iter().enumerate() {
+        for y in 0..face {
+            let line: String = faces
+                .chars()
+                .flat_map(|f| (0..face).map(move |_| f))
+                .enumerate()
+                .map(|(x, f)| match f {
+                    '#' if (row, y) == (0, 0) && x % face == 0 => '.',
+                    '#' if rng.one_in(8) => '#',
+                    '#' => '.',
+                    _ => ' ',
+                })
+                .collect();
+            lines.push(line.trim_end().to_string());
         }
     }
-    (position, facing)
+    lines
+}
+
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let face = (size / 4).clamp(2, 50);
+    let net = *rng.pick(&NETS);
+    let mut lines = draw_net(net, face, rng);
+    let mut path = rng.range(1..=face * 2).to_string();
+    for _ in 0..size.max(1) {
+        path.push(*rng.pick(&['L', 'R']));
+        path.push_str(&rng.range(1..=face * 2).to_string());
+    }
+    lines.push(String::new());
+    lines.push(path);
+    lines.join("\n")
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input<'a> = (Board, Vec<Instruction>);
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1((grid, instructions): Self::Input<'_>) -> Result<Answer, SolveError> {
+        let (position, facing) = execute_map(&grid, &instructions, &mut ())
+            .ok_or_else(|| SolveError::unsolvable(22, "the path walks off the map"))?;
+        Ok(password(position, &facing).into())
+    }
+
+    fn part2((grid, instructions): Self::Input<'_>) -> Result<Answer, SolveError> {
+        let cube = Cube::fold(&grid)
+            .ok_or_else(|| SolveError::unsolvable(22, "the map does not fold into a cube"))?;
+        let (position, facing) = execute_cube(&grid, &cube, &instructions, &mut ())
+            .ok_or_else(|| SolveError::unsolvable(22, "the path walks off the cube"))?;
+        Ok(password(position, &facing).into())
+    }
+}
+
+impl Visualize for Day22 {
+    fn visualize((grid, instructions): Self::Input<'_>, part: Part, recorder: &mut dyn Recorder) {
+        match part {
+            Part::One => execute_map(&grid, &instructions, recorder),
+            // There is nothing to watch on a map that doesn't fold
+            Part::Two => Cube::fold(&grid)
+                .and_then(|cube| execute_cube(&grid, &cube, &instructions, recorder)),
+        };
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "        ...#
-        .#..
-        #...
-        ....
-...#.......#
-........#...
-..#....#....
-..........#.
-        ...#....
-        .....#..
-        .#......
-        ......#.
-
-10R5L5R10L4R5L5";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let (grid, instructions) = parse_file(TEST_STR);
-        assert_eq!(grid[0][0], GridState::Void);
-        assert_eq!(grid[1][8], GridState::Path);
-        assert_eq!(grid[1][9], GridState::Wall);
+        let (grid, instructions) = parse_file(TEST_STR).unwrap();
+        assert_eq!(grid[(0, 0)], GridState::Void);
+        assert_eq!(grid[(8, 1)], GridState::Path);
+        assert_eq!(grid[(9, 1)], GridState::Wall);
         assert_eq!(instructions[0], Instruction::Move(10));
         assert_eq!(instructions[1], Instruction::Right);
         assert_eq!(instructions[2], Instruction::Move(5));
@@ -330,54 +467,99 @@ mod tests {
 
     #[test]
     fn test_get_next_position_map() {
-        let (grid, _) = parse_file(TEST_STR);
-        assert_eq!(get_next_position_map(&grid, &(0, 8), &Facing::East), (0, 9));
+        let (grid, _) = parse_file(TEST_STR).unwrap();
+        let start = Point::new(8, 0);
+        assert_eq!(
+            get_next_position_map(&grid, &start, &Facing::Right),
+            Point::new(9, 0)
+        );
         assert_eq!(
-            get_next_position_map(&grid, &(0, 8), &Facing::West),
-            (0, 11)
+            get_next_position_map(&grid, &start, &Facing::Left),
+            Point::new(11, 0)
         );
         assert_eq!(
-            get_next_position_map(&grid, &(0, 8), &Facing::South),
-            (1, 8)
+            get_next_position_map(&grid, &start, &Facing::Down),
+            Point::new(8, 1)
         );
         assert_eq!(
-            get_next_position_map(&grid, &(0, 8), &Facing::North),
-            (11, 8)
+            get_next_position_map(&grid, &start, &Facing::Up),
+            Point::new(8, 11)
         );
     }
 
     #[test]
     fn test_execute() {
-        let (grid, instructions) = parse_file(TEST_STR);
-        let (position, facing) = execute(false, &grid, &instructions);
-        assert_eq!(position.0, 5);
-        assert_eq!(position.1, 7);
-        assert_eq!(facing, Facing::East);
+        let (grid, instructions) = parse_file(TEST_STR).unwrap();
+        let (position, facing) = execute_map(&grid, &instructions, &mut ()).unwrap();
+        assert_eq!(position, Point::new(7, 5));
+        assert_eq!(facing, Facing::Right);
+        assert_eq!(password(position, &facing), 6032);
+    }
+
+    #[test]
+    fn test_cube_next_position() {
+        let (grid, _) = parse_file(TEST_STR).unwrap();
+        let cube = Cube::fold(&grid).unwrap();
+        assert_eq!(cube.size, 4);
+        // The puzzle's examples: A to B, and C to D
+        assert_eq!(
+            cube.next_position(&grid, &Point::new(11, 5), &Facing::Right),
+            (Point::new(14, 8), Facing::Down)
+        );
+        assert_eq!(
+            cube.next_position(&grid, &Point::new(10, 11), &Facing::Down),
+            (Point::new(1, 7), Facing::Up)
+        );
         assert_eq!(
-            (position.0 + 1) * 1000 + (position.1 + 1) * 4 + facing.value(),
-            6032
-        )
+            cube.next_position(&grid, &Point::new(9, 5), &Facing::Right),
+            (Point::new(10, 5), Facing::Right)
+        );
     }
-}
 
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let (grid, instructions) = parse_file(&contents);
-    let (position, facing) = execute(&grid, &instructions);
+    #[test]
+    fn test_fold_every_net() {
+        for net in NETS {
+            let map = draw_net(net, 3, &mut Rng::new(0)).join("\n");
+            let (grid, _) = parse_file(&(map + "\n\n1")).unwrap();
+            let cube = Cube::fold(&grid).unwrap();
+            // Walking off any edge and straight back returns to where we started
+            for ((x, y), tile) in grid.iter() {
+                if *tile == GridState::Void {
+                    continue;
+                }
+                let position = Point::from((x, y));
+                for facing in Facing::ALL {
+                    let (next, next_facing) = cube.next_position(&grid, &position, &facing);
+                    let back = next_facing.turn_right().turn_right();
+                    let (previous, previous_facing) = cube.next_position(&grid, &next, &back);
+                    assert_eq!(previous, position);
+                    assert_eq!(previous_facing, facing.turn_right().turn_right());
+                }
+            }
+        }
+        let (grid, _) = parse_file("...\n\n1").unwrap();
+        assert!(Cube::fold(&grid).is_none());
+    }
 
-    println!(
-        "{}",
-        (position.0 + 1) * 1000 + (position.1 + 1) * 4 + facing.value()
-    );
-}
+    #[test]
+    fn test_execute_cube() {
+        let (grid, instructions) = parse_file(TEST_STR).unwrap();
+        let cube = Cube::fold(&grid).unwrap();
+        let (position, facing) = execute_cube(&grid, &cube, &instructions, &mut ()).unwrap();
+        assert_eq!(position, Point::new(6, 4));
+        assert_eq!(facing, Facing::Up);
+        assert_eq!(password(position, &facing), 5031);
+    }
 
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    // let (mut grid, sensors) = parse_file(&contents);
-    // println!(
-    //     "{}",
-    //     find_tuning(&mut grid, &sensors, 4_000_000, 4_000_000).unwrap()
-    // );
+    #[test]
+    fn test_part2_without_a_cube() {
+        let input = parse_file("...\n\n1").unwrap();
+        assert_eq!(
+            Day22::part2(input),
+            Err(SolveError::unsolvable(
+                22,
+                "the map does not fold into a cube"
+            ))
+        );
+    }
 }
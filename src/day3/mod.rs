@@ -1,24 +1,35 @@
 use std::collections::HashSet;
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
-type Rucksack = (Vec<char>, Vec<char>);
+pub type Rucksack = (Vec<char>, Vec<char>);
 
-fn parse_file(text: &str) -> Vec<Rucksack> {
+pub fn parse_file(text: &str) -> Result<Vec<Rucksack>, ParseError> {
     let lines = text.lines();
     lines
-        .map(|l| {
+        .enumerate()
+        .map(|(i, l)| {
+            if let Some(offset) = l.find(|c: char| !c.is_ascii_alphabetic()) {
+                return Err(ParseError::new(3, i, l, "items must be letters").at_column(offset));
+            }
+            if l.len() % 2 != 0 {
+                return Err(ParseError::new(3, i, l, "odd number of items"));
+            }
             let chars = l.chars();
             let split = chars.clone().count() / 2;
-            (
+            Ok((
                 chars.clone().take(split).collect::<Vec<char>>(),
                 chars.skip(split).collect::<Vec<char>>(),
-            )
+            ))
         })
         .collect()
 }
 
-fn find_overlap(rucksack: &Rucksack) -> char {
+pub fn find_overlap(rucksack: &Rucksack) -> char {
     let left = rucksack.0.iter().collect::<HashSet<_>>();
     let right = rucksack.1.iter().collect::<HashSet<_>>();
     let inter = left.intersection(&right).collect::<Vec<_>>();
@@ -26,11 +37,11 @@ fn find_overlap(rucksack: &Rucksack) -> char {
     ***c
 }
 
-fn find_overlaps(rucksacks: &Vec<Rucksack>) -> Vec<char> {
-    rucksacks.iter().map(|r| find_overlap(r)).collect()
+pub fn find_overlaps(rucksacks: &[Rucksack]) -> Vec<char> {
+    rucksacks.iter().map(find_overlap).collect()
 }
 
-fn find_elf_overlap(rucksacks: (&Rucksack, &Rucksack, &Rucksack)) -> char {
+pub fn find_elf_overlap(rucksacks: (&Rucksack, &Rucksack, &Rucksack)) -> char {
     let pack1 = [rucksacks.0 .0.clone(), rucksacks.0 .1.clone()].concat();
 
     let pack2 = [rucksacks.1 .0.clone(), rucksacks.1 .1.clone()].concat();
@@ -39,7 +50,7 @@ fn find_elf_overlap(rucksacks: (&Rucksack, &Rucksack, &Rucksack)) -> char {
         .iter()
         .collect::<HashSet<_>>()
         .intersection(&pack2.iter().collect::<HashSet<_>>())
-        .map(|c| *c)
+        .copied()
         .collect::<HashSet<_>>();
 
     let pack3_set = pack3.iter().collect::<HashSet<_>>();
@@ -48,14 +59,14 @@ fn find_elf_overlap(rucksacks: (&Rucksack, &Rucksack, &Rucksack)) -> char {
     ***intersect.collect::<Vec<_>>().first().unwrap()
 }
 
-fn find_elf_overlaps(rucksacks: &Vec<Rucksack>) -> Vec<char> {
+pub fn find_elf_overlaps(rucksacks: &[Rucksack]) -> Vec<char> {
     rucksacks
         .chunks(3)
         .map(|chunk| find_elf_overlap((&chunk[0], &chunk[1], &chunk[2])))
         .collect()
 }
 
-fn sum_overlaps(overlaps: &Vec<char>) -> u32 {
+pub fn sum_overlaps(overlaps: &[char]) -> u32 {
     let values = overlaps.iter().map(|c| {
         if c.is_uppercase() {
             *c as u8 - 38
@@ -66,26 +77,72 @@ fn sum_overlaps(overlaps: &Vec<char>) -> u32 {
     let sum: u32 = values.map(|v| v as u32).sum();
     sum
 }
+/// `size` random rucksacks, rounded up to whole groups of three. Each has exactly one item in
+/// both compartments, and each group exactly one badge in common.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.max(1).div_ceil(3) {
+        let mut pool = items.clone();
+        rng.shuffle(&mut pool);
+        let badge = pool.pop().unwrap();
+        // Every elf in the group packs from their own 17 items, so only the badge is shared
+        for own in pool.chunks(17) {
+            let (shared, rest) = own.split_first().unwrap();
+            let (left_items, right_items) = rest.split_at(8);
+            let half = rng.range(2..=12);
+            let mut left = vec![*shared, badge];
+            let mut right = vec![*shared];
+            left.extend((2..half).map(|_| *rng.pick(left_items)));
+            right.extend((1..half).map(|_| *rng.pick(right_items)));
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            rucksacks.push(left.into_iter().chain(right).collect::<String>());
+        }
+    }
+    rucksacks.join("\n")
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<Rucksack>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        let rucksacks = parse_file(text)?;
+        if rucksacks.len() % 3 != 0 {
+            return Err(ParseError::input(
+                3,
+                "rucksacks must come in groups of three",
+            ));
+        }
+        Ok(rucksacks)
+    }
+
+    fn part1(rucksacks: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(sum_overlaps(&find_overlaps(&rucksacks)).into())
+    }
+
+    fn part2(rucksacks: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(sum_overlaps(&find_elf_overlaps(&rucksacks)).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let rucksacks = parse_file(TEST_STR);
+        let rucksacks = parse_file(TEST_STR).unwrap();
         assert_eq!(rucksacks.get(2).unwrap().0.len(), 9);
     }
 
     #[test]
     fn test_find_overlap() {
-        let rucksacks = parse_file(TEST_STR);
+        let rucksacks = parse_file(TEST_STR).unwrap();
         let overlaps: Vec<char> = find_overlaps(&rucksacks);
         assert_eq!(overlaps[0], 'p');
         assert_eq!(overlaps[1], 'L');
@@ -93,40 +150,22 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_sum_overlaps() {
-        let rucksacks = parse_file(TEST_STR);
+        let rucksacks = parse_file(TEST_STR).unwrap();
         let overlaps: Vec<char> = find_overlaps(&rucksacks);
         assert_eq!(sum_overlaps(&overlaps), 157);
     }
 
     #[test]
     fn test_find_elf_overlap() {
-        let rucksacks = parse_file(TEST_STR);
+        let rucksacks = parse_file(TEST_STR).unwrap();
         let overlap: char = find_elf_overlap((&rucksacks[0], &rucksacks[1], &rucksacks[2]));
         assert_eq!(overlap, 'r');
     }
 
     #[test]
     fn test_sum_elf_overlaps() {
-        let rucksacks = parse_file(TEST_STR);
+        let rucksacks = parse_file(TEST_STR).unwrap();
         let overlaps: Vec<char> = find_elf_overlaps(&rucksacks);
         assert_eq!(sum_overlaps(&overlaps), 70);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-
-    let rucksacks = parse_file(&contents);
-    let overlaps: Vec<char> = find_overlaps(&rucksacks);
-    println!("{}", sum_overlaps(&overlaps));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let rucksacks = parse_file(&contents);
-    let overlaps: Vec<char> = find_elf_overlaps(&rucksacks);
-
-    println!("{}", sum_overlaps(&overlaps));
-}
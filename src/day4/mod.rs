@@ -1,8 +1,12 @@
 use std::ops::RangeInclusive;
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
-trait ContainsRange<Idx> {
+pub trait ContainsRange<Idx> {
     fn contains_range(&self, range: &RangeInclusive<Idx>) -> bool;
 }
 
@@ -15,7 +19,7 @@ where
     }
 }
 
-trait OverlapsRange<Idx> {
+pub trait OverlapsRange<Idx> {
     fn overlaps_range(&self, range: &RangeInclusive<Idx>) -> bool;
 }
 
@@ -24,53 +28,89 @@ where
     Idx: PartialOrd<Idx>,
 {
     fn overlaps_range(&self, range: &RangeInclusive<Idx>) -> bool {
-        range.contains_range(self) || self.contains(&range.start()) || self.contains(&range.end())
+        range.contains_range(self) || self.contains(range.start()) || self.contains(range.end())
     }
 }
 
-type Elf = RangeInclusive<u32>;
-type ElfPair = (Elf, Elf);
+pub type Elf = RangeInclusive<u32>;
+pub type ElfPair = (Elf, Elf);
+
+pub fn parse_range(i: usize, line: &str, range: &str) -> Result<Elf, ParseError> {
+    let error = |message: &str| ParseError::new(4, i, line, message);
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| error("expected a range like 2-4").at(range))?;
+    let start = start
+        .parse::<u32>()
+        .map_err(|_| error("invalid section number").at(start))?;
+    let end = end
+        .parse::<u32>()
+        .map_err(|_| error("invalid section number").at(end))?;
+    Ok(start..=end)
+}
 
-fn parse_file(text: &str) -> Vec<ElfPair> {
+pub fn parse_file(text: &str) -> Result<Vec<ElfPair>, ParseError> {
     let lines = text.lines();
     lines
-        .map(|l| {
-            let elfs: Vec<RangeInclusive<u32>> = l
-                .split(",")
-                .map(|e| {
-                    let range: Vec<u32> = e.split("-").map(|i| i.parse::<u32>().unwrap()).collect();
-                    range[0]..=range[1]
-                })
-                .collect();
-            (elfs[0].clone(), elfs[1].clone())
+        .enumerate()
+        .map(|(i, l)| {
+            let (left, right) = l
+                .split_once(',')
+                .ok_or_else(|| ParseError::new(4, i, l, "expected two ranges"))?;
+            Ok((parse_range(i, l, left)?, parse_range(i, l, right)?))
         })
         .collect()
 }
 
-fn get_redundant_elfs(elfs: &Vec<ElfPair>) -> Vec<ElfPair> {
+pub fn get_redundant_elfs(elfs: &[ElfPair]) -> Vec<ElfPair> {
     elfs.iter()
         .filter(|pair| pair.0.contains_range(&pair.1) || pair.1.contains_range(&pair.0))
-        .map(|p| p.clone())
+        .cloned()
         .collect()
 }
 
-fn get_overlap_elfs(elfs: &Vec<ElfPair>) -> Vec<ElfPair> {
+pub fn get_overlap_elfs(elfs: &[ElfPair]) -> Vec<ElfPair> {
     elfs.iter()
         .filter(|pair| pair.0.overlaps_range(&pair.1))
-        .map(|p| p.clone())
+        .cloned()
         .collect()
 }
 
+/// `size` random pairs of section assignments
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let range = |rng: &mut Rng| {
+        let (a, b) = (rng.range(1..=99), rng.range(1..=99));
+        format!("{}-{}", a.min(b), a.max(b))
+    };
+    (0..size.max(1))
+        .map(|_| format!("{},{}", range(rng), range(rng)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Vec<ElfPair>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(elfs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_redundant_elfs(&elfs).len().into())
+    }
+
+    fn part2(elfs: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_overlap_elfs(&elfs).len().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_contains_range() {
@@ -98,40 +138,22 @@ mod tests {
 
     #[test]
     fn test_parse_file() {
-        let elfs = parse_file(TEST_STR);
+        let elfs = parse_file(TEST_STR).unwrap();
         assert_eq!(*elfs.get(2).unwrap().0.start(), 5);
         assert_eq!(*elfs.get(4).unwrap().1.end(), 6);
     }
 
     #[test]
     fn test_get_redundant_elfs() {
-        let elfs = parse_file(TEST_STR);
+        let elfs = parse_file(TEST_STR).unwrap();
         let redundant_elfs = get_redundant_elfs(&elfs);
         assert_eq!(redundant_elfs.len(), 2);
     }
 
     #[test]
     fn test_get_overlap_elfs() {
-        let elfs = parse_file(TEST_STR);
+        let elfs = parse_file(TEST_STR).unwrap();
         let overlap_elfs = get_overlap_elfs(&elfs);
         assert_eq!(overlap_elfs.len(), 4);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let elfs = parse_file(&contents);
-    let redundant_elfs = get_redundant_elfs(&elfs);
-
-    println!("{}", redundant_elfs.len());
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let elfs = parse_file(&contents);
-    let overlap_elfs = get_overlap_elfs(&elfs);
-
-    println!("{}", overlap_elfs.len());
-}
@@ -3,15 +3,21 @@ use std::fmt;
 
 use regex::Regex;
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    info,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+    trace,
+};
 
 #[derive(Debug)]
-struct Crate {
-    id: char,
+pub struct Crate {
+    pub id: char,
 }
 
-struct Stack {
-    crates: Vec<Crate>,
+pub struct Stack {
+    pub crates: Vec<Crate>,
 }
 
 impl fmt::Debug for Stack {
@@ -24,64 +30,82 @@ impl fmt::Debug for Stack {
     }
 }
 
-struct Instruction {
-    quantity: u32,
-    from: u8,
-    to: u8,
+pub struct Instruction {
+    pub quantity: u32,
+    pub from: u8,
+    pub to: u8,
 }
-struct Input {
-    stacks: Vec<Stack>,
-    instructions: Vec<Instruction>,
+pub struct Input {
+    pub stacks: Vec<Stack>,
+    pub instructions: Vec<Instruction>,
 }
 
-fn parse_file(text: &str) -> Input {
+pub fn parse_file(text: &str) -> Result<Input, ParseError> {
     // let lines = text.lines();
-    let text_parts: Vec<&str> = text.split("\n\n").collect();
-    let stacks_str = text_parts.first().unwrap();
-    let stacks_count = (stacks_str.lines().last().unwrap().len() + 1) / 4;
+    let (stacks_str, instructions_str) = text
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::input(5, "expected a blank line between stacks and moves"))?;
+    let stacks_count = (stacks_str.lines().last().unwrap_or_default().len() + 1) / 4;
     let mut stacks: Vec<Stack> = (0..stacks_count)
         .map(|_| Stack { crates: Vec::new() })
         .collect();
-    println!("Stacks count {}", stacks_count);
     stacks_str.lines().rev().skip(1).for_each(|l| {
-        for i in 0..stacks_count {
-            let crate_id = l
-                .get(i * 4 + 1..i * 4 + 1 + 1)
-                .unwrap()
-                .chars()
-                .last()
-                .unwrap();
+        for (i, stack) in stacks.iter_mut().enumerate() {
+            // Trailing empty stacks may have been trimmed from the line
+            let crate_id = match l.get(i * 4 + 1..i * 4 + 1 + 1) {
+                Some(id) => id.chars().last().unwrap(),
+                None => continue,
+            };
             if crate_id == ' ' {
                 continue;
             }
-            println!("crate id {}", crate_id);
-            stacks[i].crates.push(Crate { id: crate_id });
-            println!("crated {:?}", stacks[i]);
+            stack.crates.push(Crate { id: crate_id });
+            trace!("pushed {} onto {:?}", crate_id, stack);
         }
     });
 
-    let instructions_str = text_parts.last().unwrap();
-    let instructions_regex = Regex::new(r"move (?P<q>\d+) from (?P<f>\d+) to (?P<t>\d+)").unwrap();
+    let first_line = stacks_str.lines().count() + 1;
+    let instructions_regex =
+        Regex::new(r"^move (?P<q>\d+) from (?P<f>\d+) to (?P<t>\d+)$").unwrap();
     let instructions: Vec<Instruction> = instructions_str
         .lines()
-        .map(|l| {
-            let captures = instructions_regex.captures(l).unwrap();
-            Instruction {
-                quantity: captures.name("q").unwrap().as_str().parse::<u32>().unwrap(),
-                from: captures.name("f").unwrap().as_str().parse::<u8>().unwrap(),
-                to: captures.name("t").unwrap().as_str().parse::<u8>().unwrap(),
-            }
+        .enumerate()
+        .map(|(i, l)| {
+            let error = |message: &str| ParseError::new(5, first_line + i, l, message);
+            let captures = instructions_regex
+                .captures(l)
+                .ok_or_else(|| error("expected 'move N from A to B'"))?;
+            let number = |name: &str| {
+                let m = captures.name(name).unwrap();
+                m.as_str()
+                    .parse::<u32>()
+                    .map_err(|_| error("number too large").at(m.as_str()))
+            };
+            let stack = |name: &str| {
+                let m = captures.name(name).unwrap();
+                match number(name)? {
+                    n @ 1.. if n as usize <= stacks_count => Ok(n as u8),
+                    _ => Err(error("no such stack").at(m.as_str())),
+                }
+            };
+            Ok(Instruction {
+                quantity: number("q")?,
+                from: stack("f")?,
+                to: stack("t")?,
+            })
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
+    info!("{} stacks, {} moves", stacks_count, instructions.len());
 
-    Input {
+    Ok(Input {
         stacks,
         instructions,
-    }
+    })
 }
 
-fn execute_instructions(input: &mut Input) {
+pub fn execute_instructions(input: &mut Input) {
     input.instructions.iter().for_each(|i| {
+        trace!("move {} from {} to {}", i.quantity, i.from, i.to);
         for _ in 1..=i.quantity {
             let c = input
                 .stacks
@@ -96,13 +120,14 @@ fn execute_instructions(input: &mut Input) {
                 .unwrap()
                 .crates
                 .push(c);
-            print!("{:?}\n\n", input.stacks);
+            trace!("{:?}", input.stacks);
         }
     });
 }
 
-fn execute_instructions_2(input: &mut Input) {
+pub fn execute_instructions_2(input: &mut Input) {
     input.instructions.iter().for_each(|i| {
+        trace!("move {} from {} to {}", i.quantity, i.from, i.to);
         let stack = input.stacks.get_mut(i.from as usize - 1).unwrap();
         let mut c = stack
             .crates
@@ -113,12 +138,11 @@ fn execute_instructions_2(input: &mut Input) {
             .unwrap()
             .crates
             .append(&mut c);
-
-        print!("{:?}\n\n", input.stacks);
+        trace!("{:?}", input.stacks);
     });
 }
 
-fn get_top_crates(input: Input) -> Vec<char> {
+pub fn get_top_crates(input: Input) -> Vec<char> {
     input
         .stacks
         .iter()
@@ -126,23 +150,80 @@ fn get_top_crates(input: Input) -> Vec<char> {
         .collect()
 }
 
+/// Nine random stacks of crates and `size` moves between them. No move takes more crates
+/// than a stack holds, and no stack is ever left empty.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    // With more crates than stacks, some stack always has a crate to spare
+    let mut stacks: Vec<Vec<char>> = (0..9)
+        .map(|s| {
+            let height = rng.range(if s == 0 { 2 } else { 1 }..=8);
+            (0..height).map(|_| rng.uppercase()).collect()
+        })
+        .collect();
+    let height = stacks.iter().map(|s| s.len()).max().unwrap();
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|s| match s.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect();
+    lines.push(
+        (1..=stacks.len())
+            .map(|n| format!(" {} ", n))
+            .collect::<Vec<String>>()
+            .join(" "),
+    );
+    lines.push(String::new());
+
+    for _ in 0..size.max(1) {
+        let spare: Vec<usize> = (0..stacks.len()).filter(|s| stacks[*s].len() > 1).collect();
+        let from = *rng.pick(&spare);
+        let to = (from + rng.range(1..=stacks.len() - 1)) % stacks.len();
+        let quantity = rng.range(1..=stacks[from].len() - 1);
+        let at = stacks[from].len() - quantity;
+        let moved = stacks[from].split_off(at);
+        stacks[to].extend(moved);
+        lines.push(format!("move {} from {} to {}", quantity, from + 1, to + 1));
+    }
+    lines.join("\n")
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = Input;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(mut input: Self::Input<'_>) -> Result<Answer, SolveError> {
+        execute_instructions(&mut input);
+        Ok(String::from_iter(get_top_crates(input).iter()).into())
+    }
+
+    fn part2(mut input: Self::Input<'_>) -> Result<Answer, SolveError> {
+        execute_instructions_2(&mut input);
+        Ok(String::from_iter(get_top_crates(input).iter()).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let input = parse_file(TEST_STR);
+        let input = parse_file(TEST_STR).unwrap();
         assert_eq!(input.instructions.len(), 4);
         assert_eq!(input.instructions.first().unwrap().from, 2);
         assert_eq!(input.instructions.last().unwrap().quantity, 1);
@@ -154,10 +235,19 @@ move 1 from 1 to 2";
         );
     }
 
+    #[test]
+    fn test_parse_unknown_stack() {
+        let error = parse_file("[A]\n 1 \n\nmove 1 from 1 to 2").err().unwrap();
+        assert_eq!((error.line, error.column), (4, 18));
+        assert_eq!(error.message, "no such stack");
+        // The same text earlier in the line is not the stack that's missing
+        let error = parse_file("[A]\n 1 \n\nmove 2 from 1 to 2").err().unwrap();
+        assert_eq!((error.line, error.column), (4, 18));
+    }
+
     #[test]
     fn test_execute_instructions() {
-        let mut input = parse_file(TEST_STR);
-        println!("{:?}", input.stacks);
+        let mut input = parse_file(TEST_STR).unwrap();
         execute_instructions(&mut input);
         assert_eq!(input.stacks.first().unwrap().crates.len(), 1);
         assert_eq!(input.stacks.get(1).unwrap().crates.len(), 1);
@@ -167,8 +257,7 @@ move 1 from 1 to 2";
 
     #[test]
     fn test_execute_instructions_2() {
-        let mut input = parse_file(TEST_STR);
-        println!("{:?}", input.stacks);
+        let mut input = parse_file(TEST_STR).unwrap();
         execute_instructions_2(&mut input);
         assert_eq!(input.stacks.first().unwrap().crates.len(), 1);
         assert_eq!(input.stacks.get(1).unwrap().crates.len(), 1);
@@ -176,26 +265,25 @@ move 1 from 1 to 2";
         assert_eq!(input.stacks.get(2).unwrap().crates.last().unwrap().id, 'D');
     }
 
+    #[test]
+    fn test_trace() {
+        let (input, lines) = trace::capture(5, trace::Level::Info, || parse_file(TEST_STR));
+        assert_eq!(lines, vec!["day 5 info: 3 stacks, 4 moves"]);
+        let mut input = input.unwrap();
+        let ((), lines) =
+            trace::capture(5, trace::Level::Trace, || execute_instructions(&mut input));
+        assert_eq!(lines[0], "day 5 trace: move 1 from 2 to 1");
+        assert_eq!(lines.len(), 4 + 1 + 3 + 2 + 1);
+        let ((), lines) = trace::capture(5, trace::Level::Off, || {
+            execute_instructions_2(&mut parse_file(TEST_STR).unwrap())
+        });
+        assert!(lines.is_empty());
+    }
+
     #[test]
     fn test_get_top_crates() {
-        let mut input = parse_file(TEST_STR);
+        let mut input = parse_file(TEST_STR).unwrap();
         execute_instructions(&mut input);
         assert_eq!(get_top_crates(input), ['C', 'M', 'Z']);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let mut input = parse_file(&contents);
-    execute_instructions(&mut input);
-    println!("{:?}", String::from_iter(get_top_crates(input).iter()));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let mut input = parse_file(&contents);
-    execute_instructions_2(&mut input);
-    println!("{:?}", String::from_iter(get_top_crates(input).iter()));
-}
@@ -1,13 +1,22 @@
 use std::collections::HashSet;
 
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
-fn parse_file(text: &str) -> &str {
-    text
+pub fn parse_file(text: &str) -> Result<&str, ParseError> {
+    match text.find(|c: char| !c.is_ascii_lowercase()) {
+        Some(offset) => {
+            Err(ParseError::new(6, 0, text, "expected only lowercase letters").at_column(offset))
+        }
+        None => Ok(text),
+    }
 }
 
-fn find_first_unique_index(size: usize, text: &str) -> u32 {
-    for i in size..text.len() {
+pub fn find_first_unique_index(size: usize, text: &str) -> u32 {
+    for i in size..=text.len() {
         let slice = &text[i - size..i];
         let set = slice.chars().collect::<HashSet<_>>();
         if set.len() == size {
@@ -17,15 +26,45 @@ fn find_first_unique_index(size: usize, text: &str) -> u32 {
     0
 }
 
+/// A random datastream of `size` characters. It only uses three letters until the very end,
+/// so neither marker turns up before the last 14 characters.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut marker: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut marker);
+    marker.truncate(14);
+    (0..size.saturating_sub(marker.len()))
+        .map(|_| *rng.pick(&['x', 'y', 'z']))
+        .chain(marker)
+        .collect()
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = &'a str;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(input: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(find_first_unique_index(4, input).into())
+    }
+
+    fn part2(input: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(find_first_unique_index(14, input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_find_first_unique_index() {
-        let input = parse_file(TEST_STR);
+        let input = parse_file(TEST_STR).unwrap();
         assert_eq!(find_first_unique_index(4, input), 7);
         assert_eq!(
             find_first_unique_index(4, "bvwbjplbgvbhsrlpgdmjqwftvncz"),
@@ -45,19 +84,6 @@ mod tests {
             find_first_unique_index(14, "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"),
             26
         );
+        assert_eq!(find_first_unique_index(4, "abcd"), 4);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let input = parse_file(&contents);
-    println!("{:?}", find_first_unique_index(4, input));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let input = parse_file(&contents);
-    println!("{:?}", find_first_unique_index(14, input));
-}
@@ -1,12 +1,16 @@
-use crate::file::read_file;
+use crate::{
+    generate::Rng,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Debug)]
-struct FileSystem<'a> {
-    nodes: Vec<File<'a>>,
+pub struct FileSystem<'a> {
+    pub nodes: Vec<File<'a>>,
 }
 
 impl<'a> FileSystem<'a> {
-    fn add_file(&mut self, name: &'a str, size: usize, parent: Option<usize>) -> usize {
+    pub fn add_file(&mut self, name: &'a str, size: usize, parent: Option<usize>) -> usize {
         let index = self.nodes.len();
         let node = File {
             id: index,
@@ -16,15 +20,14 @@ impl<'a> FileSystem<'a> {
             parent,
         };
         self.nodes.push(node);
-        match parent {
-            Some(i) => self.nodes[i].children.push(index),
-            None => (),
+        if let Some(i) = parent {
+            self.nodes[i].children.push(index);
         }
 
         index
     }
 
-    fn total_size(&self, file: usize) -> usize {
+    pub fn total_size(&self, file: usize) -> usize {
         match self.nodes[file].size {
             0 => self.nodes[file]
                 .children
@@ -35,8 +38,7 @@ impl<'a> FileSystem<'a> {
         }
     }
 
-    #[allow(dead_code)]
-    fn print(&self) {
+    pub fn print(&self) {
         self.print_file(0, 0)
     }
 
@@ -55,67 +57,67 @@ impl<'a> FileSystem<'a> {
 }
 
 #[derive(Debug)]
-struct File<'a> {
-    id: usize,
-    name: &'a str,
-    size: usize,
-    children: Vec<usize>,
-    parent: Option<usize>,
+pub struct File<'a> {
+    pub id: usize,
+    pub name: &'a str,
+    pub size: usize,
+    pub children: Vec<usize>,
+    pub parent: Option<usize>,
 }
 
 impl<'a> File<'a> {}
 
-fn parse_file(text: &str) -> FileSystem {
+pub fn parse_file(text: &str) -> Result<FileSystem<'_>, ParseError> {
     let mut files = FileSystem { nodes: Vec::new() };
-    let mut current_file = files.add_file(&"/", 0, None);
-    let lines = text.lines();
-    lines.for_each(|l| -> () {
-        if l.starts_with("$") {
-            let mut commands = l[2..].split(" ");
+    let mut current_file = files.add_file("/", 0, None);
+    for (i, l) in text.lines().enumerate() {
+        let error = |message: &str| ParseError::new(7, i, l, message);
+        if let Some(command) = l.strip_prefix("$ ") {
+            let mut commands = command.split(' ');
             match commands.next() {
                 Some("ls") => (),
                 Some("cd") => match commands.next() {
                     Some("/") => current_file = 0,
                     Some("..") => current_file = files.nodes[current_file].parent.unwrap_or(0),
-                    Some(dir) => current_file = files.add_file(dir, 0, Some(current_file)),
-                    _ => (),
+                    Some(dir) if !dir.is_empty() => {
+                        current_file = files.add_file(dir, 0, Some(current_file))
+                    }
+                    _ => return Err(error("cd needs a directory").at(command)),
                 },
-                _ => (),
+                _ => return Err(error("unknown command").at(command)),
             }
         } else {
-            let mut parts = l.split(" ");
-            match parts.next() {
-                Some("dir") => (),
-                Some(size) => match size.parse::<usize>() {
-                    Ok(size) => {
-                        let name = parts.next().unwrap();
-                        files.add_file(name, size, Some(current_file));
-                    }
-                    _ => (),
-                },
-                _ => (),
+            let (size, name) = l
+                .split_once(' ')
+                .ok_or_else(|| error("expected a size or 'dir' and a name"))?;
+            if size == "dir" {
+                continue;
             }
+            let size = size
+                .parse::<usize>()
+                .map_err(|_| error("invalid file size").at(size))?;
+            files.add_file(name, size, Some(current_file));
         }
-    });
-    files
+    }
+    Ok(files)
 }
 
-fn get_large_directories(files: &FileSystem) -> Vec<usize> {
+pub fn get_large_directories(files: &FileSystem) -> Vec<usize> {
     files
         .nodes
         .iter()
-        .filter(|f| f.children.len() > 0 && files.total_size(f.id) <= 100000)
+        .filter(|f| !f.children.is_empty() && files.total_size(f.id) <= 100000)
         .map(|f| f.id)
         .collect()
 }
 
-fn get_smallest_deletable_directory(files: &FileSystem) -> usize {
+pub fn get_smallest_deletable_directory(files: &FileSystem) -> usize {
     let total_space: usize = 70000000;
     let used_space = files.total_size(0);
     let needed_space = 30000000 - (total_space - used_space);
     let mut smallest = usize::MAX;
     files.nodes.iter().for_each(|f| {
-        if f.children.len() == 0 {
+        if f.children.is_empty() {
             return;
         }
         let file_size = files.total_size(f.id);
@@ -126,43 +128,117 @@ fn get_smallest_deletable_directory(files: &FileSystem) -> usize {
     smallest
 }
 
+/// A random word for naming files and directories
+fn word(rng: &mut Rng) -> String {
+    (0..rng.range(1..=8)).map(|_| rng.lowercase()).collect()
+}
+
+/// Lists `dir`, then visits each of its subdirectories in turn
+fn explore(
+    dir: usize,
+    children: &[Vec<usize>],
+    files: &[Vec<usize>],
+    names: &[String],
+    rng: &mut Rng,
+    lines: &mut Vec<String>,
+) {
+    lines.push("$ ls".to_string());
+    let mut entries: Vec<String> = children[dir]
+        .iter()
+        .map(|child| format!("dir {}", names[*child]))
+        .collect();
+    for (i, size) in files[dir].iter().enumerate() {
+        let name = match rng.one_in(2) {
+            true => format!("{}{}.{}", word(rng), i, word(rng)),
+            false => format!("{}{}", word(rng), i),
+        };
+        entries.push(format!("{} {}", size, name));
+    }
+    rng.shuffle(&mut entries);
+    lines.extend(entries);
+    for child in children[dir].iter() {
+        lines.push(format!("$ cd {}", names[*child]));
+        explore(*child, children, files, names, rng, lines);
+        lines.push("$ cd ..".to_string());
+    }
+}
+
+/// A random terminal session exploring `size` directories. The files fill about 50000000 of
+/// the disk, so part 2 always has something to delete.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    // Directory 0 is the root, and every other directory hangs off an earlier one
+    let mut children: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut names = vec!["/".to_string()];
+    for dir in 1..size.max(1) {
+        children[rng.below(dir)].push(dir);
+        children.push(Vec::new());
+        names.push(format!("{}{}", word(rng), dir));
+    }
+    let mut files: Vec<Vec<usize>> = (0..children.len())
+        .map(|_| {
+            (0..rng.range(0..=5))
+                .map(|_| rng.range(1000..=300000))
+                .collect()
+        })
+        .collect();
+    let total: usize = files.iter().flatten().sum();
+    match total {
+        0 => files[0].push(50_000_000),
+        total => files
+            .iter_mut()
+            .flatten()
+            .for_each(|size| *size = (*size * 50_000_000 / total).max(1)),
+    }
+
+    let mut lines = vec!["$ cd /".to_string()];
+    explore(0, &children, &files, &names, rng, &mut lines);
+    lines.join("\n")
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = FileSystem<'a>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(files: Self::Input<'_>) -> Result<Answer, SolveError> {
+        let large = get_large_directories(&files);
+        Ok(large
+            .iter()
+            .map(|f| files.total_size(*f))
+            .sum::<usize>()
+            .into())
+    }
+
+    fn part2(files: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_smallest_deletable_directory(&files).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let files = parse_file(TEST_STR);
+        let files = parse_file(TEST_STR).unwrap();
         files.print()
     }
 
+    #[test]
+    fn test_parse_unknown_command() {
+        let error = parse_file("$ cd /\n$ rm a").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
     #[test]
     fn test_get_large_directories() {
-        let files = parse_file(TEST_STR);
+        let files = parse_file(TEST_STR).unwrap();
         let large = get_large_directories(&files);
         assert_eq!(large.len(), 2);
         assert_eq!(
@@ -173,28 +249,8 @@ $ ls
 
     #[test]
     fn test_get_smallest_deletable_directory() {
-        let files = parse_file(TEST_STR);
+        let files = parse_file(TEST_STR).unwrap();
         let dir = get_smallest_deletable_directory(&files);
         assert_eq!(dir, 24933642);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let files = parse_file(&contents);
-    let large = get_large_directories(&files);
-
-    println!(
-        "{:?}",
-        large.iter().map(|f| files.total_size(*f)).sum::<usize>()
-    );
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let files = parse_file(&contents);
-    let dir = get_smallest_deletable_directory(&files);
-    println!("{:?}", dir);
-}
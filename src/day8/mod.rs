@@ -1,44 +1,41 @@
-use crate::file::read_file;
-
-type Forrest = Vec<Vec<u8>>;
-
-fn parse_file(text: &str) -> Forrest {
-    let forrest: Forrest = text
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| c.to_string().parse::<u8>().unwrap())
-                .collect()
-        })
-        .collect();
-
-    forrest
+use crate::{
+    generate::Rng,
+    grid::Grid,
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+    trace,
+};
+
+pub type Forrest = Grid<u8>;
+
+pub fn parse_file(text: &str) -> Result<Forrest, ParseError> {
+    Grid::parse(8, text, |c| c.to_digit(10).map(|d| d as u8))
 }
 
-fn get_row(row: usize, forrest: &Forrest) -> Vec<u8> {
-    forrest[row].clone()
+pub fn get_row(row: usize, forrest: &Forrest) -> Vec<u8> {
+    forrest.row(row).to_vec()
 }
 
-fn get_column(column: usize, forrest: &Forrest) -> Vec<u8> {
-    forrest.iter().map(|r| r[column]).collect()
+pub fn get_column(column: usize, forrest: &Forrest) -> Vec<u8> {
+    forrest.column(column).copied().collect()
 }
 
-fn is_visible(row: usize, column: usize, forrest: &Forrest) -> bool {
+pub fn is_visible(row: usize, column: usize, forrest: &Forrest) -> bool {
     let forrest_row = get_row(row, forrest);
     let forrest_column = get_column(column, forrest);
-    let tree = forrest[row][column];
+    let tree = forrest[(column, row)];
     forrest_row[0..column].iter().max().lt(&Some(&tree))
         || forrest_row[column + 1..].iter().max().lt(&Some(&tree))
         || forrest_column[0..row].iter().max().lt(&Some(&tree))
         || forrest_column[row + 1..].iter().max().lt(&Some(&tree))
 }
 
-fn count_visible_trees(forrest: &Forrest) -> usize {
+pub fn count_visible_trees(forrest: &Forrest) -> usize {
     let mut visible_count = 0;
-    for row in 0..forrest.len() {
-        for column in 0..forrest[row].len() {
+    for row in 0..forrest.height() {
+        for column in 0..forrest.width() {
             if is_visible(row, column, forrest) {
-                // println!("visible {} {}", row, column);
+                trace!("visible {} {}", row, column);
                 visible_count += 1;
             }
         }
@@ -46,8 +43,8 @@ fn count_visible_trees(forrest: &Forrest) -> usize {
     visible_count
 }
 
-fn get_scenic_score(row: usize, column: usize, forrest: &Forrest) -> usize {
-    let tree = forrest[row][column];
+pub fn get_scenic_score(row: usize, column: usize, forrest: &Forrest) -> usize {
+    let tree = forrest[(column, row)];
     let forrest_row = get_row(row, forrest);
     let forrest_column = get_column(column, forrest);
 
@@ -60,9 +57,9 @@ fn get_scenic_score(row: usize, column: usize, forrest: &Forrest) -> usize {
     }
 
     let mut right: usize = 0;
-    for i in (column + 1)..forrest_row.len() {
+    for other in forrest_row.iter().skip(column + 1) {
         right += 1;
-        if forrest_row[i] >= tree {
+        if *other >= tree {
             break;
         }
     }
@@ -76,9 +73,9 @@ fn get_scenic_score(row: usize, column: usize, forrest: &Forrest) -> usize {
     }
 
     let mut down: usize = 0;
-    for i in (row + 1)..forrest_column.len() {
+    for other in forrest_column.iter().skip(row + 1) {
         down += 1;
-        if forrest_column[i] >= tree {
+        if *other >= tree {
             break;
         }
     }
@@ -86,10 +83,10 @@ fn get_scenic_score(row: usize, column: usize, forrest: &Forrest) -> usize {
     left * right * up * down
 }
 
-fn get_most_scenic(forrest: &Forrest) -> usize {
+pub fn get_most_scenic(forrest: &Forrest) -> usize {
     let mut max = 0;
-    for row in 0..forrest.len() {
-        for column in 0..forrest[row].len() {
+    for row in 0..forrest.height() {
+        for column in 0..forrest.width() {
             let score = get_scenic_score(row, column, forrest);
             if score > max {
                 max = score;
@@ -100,26 +97,52 @@ fn get_most_scenic(forrest: &Forrest) -> usize {
     max
 }
 
+/// A random `size` by `size` patch of forest
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| char::from_digit(rng.below(10) as u32, 10).unwrap())
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = Forrest;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(forrest: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(count_visible_trees(&forrest).into())
+    }
+
+    fn part2(forrest: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(get_most_scenic(&forrest).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static TEST_STR: &str = "30373
-25512
-65332
-33549
-35390";
+    static TEST_STR: &str = include_str!("example1.txt");
 
     #[test]
     fn test_parse_file() {
-        let forrest = parse_file(TEST_STR);
-        assert_eq!(forrest.len(), 5);
-        assert_eq!(forrest[0][3], 7);
+        let forrest = parse_file(TEST_STR).unwrap();
+        assert_eq!(forrest.height(), 5);
+        assert_eq!(forrest[(3, 0)], 7);
     }
 
     #[test]
     fn test_is_visible() {
-        let forrest = parse_file(TEST_STR);
+        let forrest = parse_file(TEST_STR).unwrap();
         assert!(is_visible(0, 0, &forrest));
         assert!(is_visible(0, 3, &forrest));
         assert!(is_visible(0, 4, &forrest));
@@ -132,36 +155,20 @@ mod tests {
 
     #[test]
     fn test_count_visible_trees() {
-        let forrest = parse_file(TEST_STR);
+        let forrest = parse_file(TEST_STR).unwrap();
         assert_eq!(count_visible_trees(&forrest), 21);
     }
 
     #[test]
     fn test_get_scenic_score() {
-        let forrest = parse_file(TEST_STR);
+        let forrest = parse_file(TEST_STR).unwrap();
         assert_eq!(get_scenic_score(1, 2, &forrest), 4);
         assert_eq!(get_scenic_score(3, 2, &forrest), 8);
     }
 
     #[test]
     fn test_get_most_scenic() {
-        let forrest = parse_file(TEST_STR);
+        let forrest = parse_file(TEST_STR).unwrap();
         assert_eq!(get_most_scenic(&forrest), 8);
     }
 }
-
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let forrest = parse_file(&contents);
-
-    println!("{:?}", count_visible_trees(&forrest));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let forrest = parse_file(&contents);
-
-    println!("{:?}", get_most_scenic(&forrest));
-}
@@ -1,180 +1,212 @@
 use std::collections::HashSet;
 
-use crate::file::read_file;
-
-#[derive(PartialEq, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::{
+    generate::Rng,
+    geometry::{Direction, Point},
+    grid::Grid,
+    parse::ParseError,
+    registry::Part,
+    solution::{Answer, Solution, SolveError},
+    visualize::{Frame, Recorder, Visualize},
+};
 
-impl From<&str> for Direction {
-    fn from(s: &str) -> Self {
-        match s {
-            "U" => Self::Up,
-            "L" => Self::Left,
-            "R" => Self::Right,
-            "D" => Self::Down,
-            _ => panic!("Unknown direction {}", s),
-        }
-    }
-}
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     pub direction: Direction,
     pub distance: usize,
 }
 
-#[derive(Hash, Debug, Default, PartialEq, Eq, Clone)]
-struct Position {
-    pub x: isize,
-    pub y: isize,
-}
-
-impl Position {
-    fn move_by(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.y -= 1,
-            Direction::Down => self.y += 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        }
+pub fn parse_direction(s: &str) -> Result<Direction, String> {
+    match s {
+        "U" => Ok(Direction::Up),
+        "L" => Ok(Direction::Left),
+        "R" => Ok(Direction::Right),
+        "D" => Ok(Direction::Down),
+        _ => Err(format!("unknown direction '{}'", s)),
     }
+}
 
-    fn move_towards(&mut self, position: &Position) {
-        let vector = (position.x - self.x, position.y - self.y);
-        if vector.0.abs() == 2 && vector.1 == 0 {
-            self.x += vector.0.clamp(-1, 1);
-        } else if vector.1.abs() == 2 && vector.0 == 0 {
-            self.y += vector.1.clamp(-1, 1);
-        } else if vector.0.abs() + vector.1.abs() >= 3 {
-            self.x += vector.0.clamp(-1, 1);
-            self.y += vector.1.clamp(-1, 1);
-        }
+/// Moves a knot one step towards the knot ahead of it, unless they're already touching
+pub fn move_towards(knot: &mut Point, ahead: &Point) {
+    if knot.chebyshev(ahead) > 1 {
+        *knot += (*ahead - *knot).signum();
     }
 }
 
-fn parse_file(text: &str) -> Vec<Instruction> {
-    let instructions: Vec<Instruction> = text
-        .lines()
-        .map(|l| {
-            let parts: Vec<&str> = l.split(" ").collect();
-            Instruction {
-                direction: parts[0].into(),
-                distance: parts[1].parse::<usize>().unwrap(),
-            }
+pub fn parse_file(text: &str) -> Result<Vec<Instruction>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let error = |message: String| ParseError::new(9, i, l, message);
+            let (direction, distance) = l
+                .split_once(' ')
+                .ok_or_else(|| error("expected a direction and a distance".to_string()))?;
+            Ok(Instruction {
+                direction: parse_direction(direction).map_err(|e| error(e).at(direction))?,
+                distance: distance
+                    .parse::<usize>()
+                    .map_err(|_| error("invalid distance".to_string()).at(distance))?,
+            })
         })
-        .collect();
-
-    instructions
+        .collect()
 }
 
-fn count_tail_positions(instructions: &Vec<Instruction>) -> usize {
-    let mut head = Position {
-        ..Default::default()
-    };
-    let mut tail = Position {
-        ..Default::default()
-    };
-    let mut positions: HashSet<Position> = HashSet::new();
-    positions.insert(tail.clone());
+/// The number of distinct positions visited by the last knot of a rope
+pub fn count_rope_positions(instructions: &[Instruction], knots: usize) -> usize {
+    simulate_rope(instructions, knots, &mut ())
+}
 
+/// The smallest area the head of the rope stays inside, as its top left and bottom right
+fn bounds(instructions: &[Instruction]) -> (Point, Point) {
+    let mut head = Point::ORIGIN;
+    let (mut min, mut max) = (head, head);
     for inst in instructions.iter() {
-        // println!("{:?}", inst);
-        for _ in 0..inst.distance {
-            head.move_by(&inst.direction);
-            // println!("H {:?}", head);
-            tail.move_towards(&head);
-            // println!("T {:?}", tail);
-            positions.insert(tail.clone());
-        }
+        head += inst.direction.vector() * inst.distance as isize;
+        min = Point::new(min.x.min(head.x), min.y.min(head.y));
+        max = Point::new(max.x.max(head.x), max.y.max(head.y));
     }
+    (min, max)
+}
 
-    positions.len()
+/// Draws the positions the tail has visited as `#`, the head as `H` and every other knot by its index
+fn draw_rope(rope: &[Point], positions: &HashSet<Point>, (min, max): (Point, Point)) -> Frame {
+    let size = max - min + Point::new(1, 1);
+    let mut frame = Grid::new(size.x as usize, size.y as usize, '.');
+    for position in positions.iter() {
+        frame[*position - min] = '#';
+    }
+    for (k, knot) in rope.iter().enumerate().rev() {
+        frame[*knot - min] = match k {
+            0 => 'H',
+            k => char::from_digit(k as u32, 36).unwrap_or('T'),
+        };
+    }
+    frame
 }
 
-fn count_long_tail_positions(instructions: &Vec<Instruction>) -> usize {
-    let mut rope: Vec<Position> = (0..10)
-        .map(|_| Position {
-            ..Default::default()
-        })
-        .collect();
-    let mut positions: HashSet<Position> = HashSet::new();
-    positions.insert(rope[9].clone());
+/// Moves the rope, recording a frame after every step of the head
+pub fn simulate_rope(
+    instructions: &[Instruction],
+    knots: usize,
+    recorder: &mut dyn Recorder,
+) -> usize {
+    let mut rope = vec![Point::ORIGIN; knots];
+    let mut positions: HashSet<Point> = HashSet::new();
+    positions.insert(rope[knots - 1]);
+    // Every knot trails the head, so never strays outside its bounds
+    let bounds = bounds(instructions);
+    if recorder.is_recording() {
+        recorder.record(&draw_rope(&rope, &positions, bounds));
+    }
 
     for inst in instructions.iter() {
-        // println!("{:?}", inst);
         for _ in 0..inst.distance {
-            rope[0].move_by(&inst.direction);
+            rope[0] += inst.direction.vector();
             for k in 1..rope.len() {
-                let next_knot = rope[k - 1].clone();
-                rope[k].move_towards(&next_knot);
+                let next_knot = rope[k - 1];
+                move_towards(&mut rope[k], &next_knot);
+            }
+            positions.insert(rope[knots - 1]);
+            if recorder.is_recording() {
+                recorder.record(&draw_rope(&rope, &positions, bounds));
             }
-            // println!("{:?}", rope);
-            positions.insert(rope[9].clone());
         }
     }
 
     positions.len()
 }
 
+pub fn count_tail_positions(instructions: &[Instruction]) -> usize {
+    count_rope_positions(instructions, 2)
+}
+
+pub fn count_long_tail_positions(instructions: &[Instruction]) -> usize {
+    count_rope_positions(instructions, 10)
+}
+
+/// `size` random moves of the head of the rope
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{} {}", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1..=20)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input<'a> = Vec<Instruction>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_file(text)
+    }
+
+    fn part1(instructions: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(count_tail_positions(&instructions).into())
+    }
+
+    fn part2(instructions: Self::Input<'_>) -> Result<Answer, SolveError> {
+        Ok(count_long_tail_positions(&instructions).into())
+    }
+}
+
+impl Visualize for Day9 {
+    fn visualize(instructions: Self::Input<'_>, part: Part, recorder: &mut dyn Recorder) {
+        let knots = match part {
+            Part::One => 2,
+            Part::Two => 10,
+        };
+        simulate_rope(&instructions, knots, recorder);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::Frames;
+
+    static TEST_STR: &str = include_str!("example1.txt");
 
-    static TEST_STR: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    static TEST_STR_2: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
+    static TEST_STR_2: &str = include_str!("example2.txt");
 
     #[test]
     fn test_parse_file() {
-        let instructions = parse_file(TEST_STR);
+        let instructions = parse_file(TEST_STR).unwrap();
         assert_eq!(instructions.len(), 8);
         assert_eq!(instructions[3].distance, 1);
         assert_eq!(instructions[6].direction, Direction::Left);
     }
 
+    #[test]
+    fn test_move_towards() {
+        let mut tail = Point::new(1, 1);
+        move_towards(&mut tail, &Point::new(2, 2));
+        assert_eq!(tail, Point::new(1, 1));
+        move_towards(&mut tail, &Point::new(3, 1));
+        assert_eq!(tail, Point::new(2, 1));
+        move_towards(&mut tail, &Point::new(3, 3));
+        assert_eq!(tail, Point::new(3, 2));
+    }
+
     #[test]
     fn test_count_tail_positions() {
-        let instructions = parse_file(TEST_STR);
+        let instructions = parse_file(TEST_STR).unwrap();
         assert_eq!(count_tail_positions(&instructions), 13);
     }
 
     #[test]
     fn test_count_long_tail_positions() {
-        let instructions = parse_file(TEST_STR_2);
+        let instructions = parse_file(TEST_STR_2).unwrap();
         assert_eq!(count_long_tail_positions(&instructions), 36);
     }
-}
 
-#[allow(dead_code)]
-pub fn part1() {
-    let contents = read_file(module_path!());
-    let instructions = parse_file(&contents);
-
-    println!("{:?}", count_tail_positions(&instructions));
-}
-
-#[allow(dead_code)]
-pub fn part2() {
-    let contents = read_file(module_path!());
-    let instructions = parse_file(&contents);
-
-    println!("{:?}", count_long_tail_positions(&instructions));
+    #[test]
+    fn test_simulate_rope_frames() {
+        let instructions = parse_file(TEST_STR).unwrap();
+        let mut frames = Frames::default();
+        assert_eq!(simulate_rope(&instructions, 2, &mut frames), 13);
+        assert_eq!(frames.0.len(), 25);
+        assert_eq!(frames.0[0].row(4), &['H', '.', '.', '.', '.', '.']);
+        assert_eq!(frames.0[4].row(4), &['#', '#', '#', '1', 'H', '.']);
+    }
 }
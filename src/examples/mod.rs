@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    answers::{Answers, AnswersError},
+    registry::Day,
+};
+
+pub const EXAMPLES_FILE: &str = "examples.toml";
+
+/// A worked example from the puzzle text, checked in as `src/dayN/exampleK.txt`
+#[derive(Debug)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    /// The expected answers, from the `[exampleK]` table in the day's `examples.toml`
+    pub answers: Answers,
+}
+
+fn day_directory(day: &Day) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(day.module())
+}
+
+fn read(path: &Path) -> Result<String, AnswersError> {
+    fs::read_to_string(path).map_err(|error| AnswersError::Unreadable {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Every example fixture for `day`, sorted by name. Examples without a table in
+/// `examples.toml` have no expected answers.
+pub fn discover(day: &Day) -> Result<Vec<Example>, AnswersError> {
+    let directory = day_directory(day);
+    let unreadable = |error| AnswersError::Unreadable {
+        path: directory.clone(),
+        error,
+    };
+    let mut names: Vec<String> = fs::read_dir(&directory)
+        .map_err(unreadable)?
+        .map(|entry| entry.map(|e| e.path()).map_err(unreadable))
+        .collect::<Result<Vec<PathBuf>, AnswersError>>()?
+        .iter()
+        .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+        .filter_map(|path| path.file_stem()?.to_str())
+        .filter(|stem| stem.starts_with("example"))
+        .map(|stem| stem.to_string())
+        .collect();
+    names.sort();
+
+    let expected_path = directory.join(EXAMPLES_FILE);
+    let expected = match expected_path.exists() {
+        true => read(&expected_path)?
+            .parse::<toml::Value>()
+            .map_err(|e| AnswersError::Invalid(format!("{}: {}", expected_path.display(), e)))?,
+        false => toml::Value::Table(Default::default()),
+    };
+    for key in expected.as_table().into_iter().flatten().map(|(k, _)| k) {
+        if !names.contains(key) {
+            return Err(AnswersError::Invalid(format!(
+                "{}: no {}.txt for [{}]",
+                expected_path.display(),
+                key,
+                key
+            )));
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let answers = match expected.get(&name) {
+                Some(parts) => Answers::parse_day(day.day, &name, parts)?,
+                None => Answers::default(),
+            };
+            Ok(Example {
+                input: read(&directory.join(format!("{}.txt", name)))?,
+                name,
+                answers,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{find, Part};
+
+    #[test]
+    fn test_discover() {
+        let examples = discover(find(9).unwrap()).unwrap();
+        let names: Vec<&str> = examples.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["example1", "example2"]);
+        assert_eq!(examples[0].answers.get(9, Part::One), Some("13"));
+        assert!(examples[1].input.starts_with("R 5"));
+    }
+}
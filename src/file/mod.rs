@@ -1,7 +1,239 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::Path;
-pub fn read_file(module: &str) -> String {
-    let file_path = format!("src/{}/input.txt", module.split("::").last().unwrap()).to_owned();
-    let path = Path::new(file_path.as_str());
-    fs::read_to_string(path).expect("Should have been able to read the file")
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing at a directory of `dayN.txt` inputs
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Directory of named input sets, one `<name>/dayN.txt` per person
+pub const INPUT_SETS_DIR: &str = "inputs";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Search `AOC_INPUT_DIR`, then `src/<module>/input.txt`, then the embedded copy
+    Search,
+    Path(PathBuf),
+    Stdin,
+    /// The named input set's `inputs/<name>/<module>.txt`
+    Named(String),
+}
+
+impl From<&str> for InputSource {
+    fn from(s: &str) -> Self {
+        match s {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(PathBuf::from(path)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Unreadable { path: PathBuf, error: io::Error },
+    Stdin(io::Error),
+    NotFound { module: String, tried: Vec<PathBuf> },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Unreadable { path, error } => {
+                write!(f, "Could not read {}: {}", path.display(), error)
+            }
+            InputError::Stdin(error) => write!(f, "Could not read stdin: {}", error),
+            InputError::NotFound { module, tried } => write!(
+                f,
+                "No input found for {} (tried {})",
+                module,
+                tried
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+fn module_name(module: &str) -> &str {
+    module.split("::").last().unwrap()
+}
+
+/// Where input sets are looked for: the working directory, then the crate
+fn input_set_roots() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from(INPUT_SETS_DIR),
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(INPUT_SETS_DIR),
+    ]
+}
+
+/// The names of every input set under `roots` with an input for `module`, sorted
+fn input_sets_in(module: &str, roots: &[PathBuf]) -> Vec<String> {
+    let file = format!("{}.txt", module_name(module));
+    let mut names: Vec<String> = roots
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(&file).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The names of every input set with an input for `module`, sorted
+pub fn input_sets(module: &str) -> Vec<String> {
+    input_sets_in(module, &input_set_roots())
+}
+
+/// Paths searched for a module's input, most specific first
+fn candidates(module: &str, input_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let module = module_name(module);
+    let mut paths = Vec::new();
+    if let Some(dir) = input_dir {
+        paths.push(dir.join(format!("{}.txt", module)));
+    }
+    paths.push(Path::new("src").join(module).join("input.txt"));
+    paths.push(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(module)
+            .join("input.txt"),
+    );
+    paths
+}
+
+fn normalize(text: String) -> String {
+    text.trim_end_matches(['\n', '\r']).to_string()
+}
+
+/// The first of `paths` that can be read
+fn read_first(paths: &[PathBuf]) -> Option<String> {
+    paths
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(normalize)
+}
+
+pub fn read_file(
+    module: &str,
+    source: &InputSource,
+    embedded: Option<&'static str>,
+) -> Result<String, InputError> {
+    match source {
+        InputSource::Path(path) => {
+            fs::read_to_string(path)
+                .map(normalize)
+                .map_err(|error| InputError::Unreadable {
+                    path: path.clone(),
+                    error,
+                })
+        }
+        InputSource::Stdin => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(InputError::Stdin)?;
+            Ok(normalize(text))
+        }
+        InputSource::Search => {
+            let tried = candidates(module, env::var_os(INPUT_DIR_VAR).map(PathBuf::from));
+            read_first(&tried)
+                .or_else(|| embedded.map(|text| normalize(text.to_string())))
+                .ok_or_else(|| InputError::NotFound {
+                    module: module_name(module).to_string(),
+                    tried,
+                })
+        }
+        InputSource::Named(name) => {
+            let tried: Vec<PathBuf> = input_set_roots()
+                .iter()
+                .map(|root| root.join(name).join(format!("{}.txt", module_name(module))))
+                .collect();
+            read_first(&tried).ok_or_else(|| InputError::NotFound {
+                module: module_name(module).to_string(),
+                tried,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        let paths = candidates("advent_of_code_2022::day7", Some(PathBuf::from("/inputs")));
+        assert_eq!(paths[0], PathBuf::from("/inputs/day7.txt"));
+        assert_eq!(paths[1], PathBuf::from("src/day7/input.txt"));
+        assert!(paths[2].ends_with("src/day7/input.txt"));
+        assert_eq!(candidates("day7", None).len(), 2);
+    }
+
+    #[test]
+    fn test_read_file_path() {
+        let path = env::temp_dir().join("aoc_read_file_path.txt");
+        fs::write(&path, "1\n2\n").unwrap();
+        let text = read_file("day1", &InputSource::Path(path.clone()), None).unwrap();
+        assert_eq!(text, "1\n2");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_file_missing_path() {
+        let source = InputSource::Path(PathBuf::from("does/not/exist.txt"));
+        assert!(matches!(
+            read_file("day1", &source, None),
+            Err(InputError::Unreadable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_file_embedded() {
+        let text = read_file("day99", &InputSource::Search, Some("embedded\n")).unwrap();
+        assert_eq!(text, "embedded");
+        assert!(matches!(
+            read_file("day99", &InputSource::Search, None),
+            Err(InputError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_input_sets() {
+        let root = env::temp_dir().join("aoc_input_sets");
+        for (name, file) in [
+            ("bob", "day3.txt"),
+            ("alice", "day3.txt"),
+            ("carol", "day4.txt"),
+        ] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join(file), "").unwrap();
+        }
+        let roots = [root.join("missing"), root];
+        assert_eq!(input_sets_in("day3", &roots), vec!["alice", "bob"]);
+        assert!(input_sets_in("day3", &roots[..1]).is_empty());
+        fs::remove_dir_all(&roots[1]).unwrap();
+
+        assert!(matches!(
+            read_file("day1", &InputSource::Named("nobody".to_string()), None),
+            Err(InputError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_input_source_from() {
+        assert_eq!(InputSource::from("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from("in.txt"),
+            InputSource::Path(PathBuf::from("in.txt"))
+        );
+    }
 }
@@ -0,0 +1,217 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on an integer plane, with `y` growing downwards as on screen
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The number of king moves between two points
+    pub fn chebyshev(&self, other: &Point) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// This vector shortened to at most one step along each axis
+    pub fn signum(&self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn neighbours4(&self) -> impl Iterator<Item = Point> {
+        let point = *self;
+        Direction::ALL.into_iter().map(move |d| point + d.vector())
+    }
+
+    pub fn neighbours8(&self) -> impl Iterator<Item = Point> {
+        let point = *self;
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point::new(x, y)))
+            .filter(|v| *v != Point::ORIGIN)
+            .map(move |v| point + v)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, n: isize) -> Point {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Point {
+        Point::new(x as isize, y as isize)
+    }
+}
+
+impl From<Point> for (isize, isize) {
+    fn from(p: Point) -> (isize, isize) {
+        (p.x, p.y)
+    }
+}
+
+impl TryFrom<Point> for (usize, usize) {
+    type Error = Point;
+
+    /// Fails for points left of or above the origin
+    fn try_from(p: Point) -> Result<(usize, usize), Point> {
+        match (usize::try_from(p.x), usize::try_from(p.y)) {
+            (Ok(x), Ok(y)) => Ok((x, y)),
+            _ => Err(p),
+        }
+    }
+}
+
+/// One of the four orthogonal directions, listed clockwise from up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// The direction something is facing, for puzzles that walk and turn
+pub type Facing = Direction;
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// A single step in this direction
+    pub fn vector(&self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn reverse(&self) -> Direction {
+        Direction::ALL[(*self as usize + 2) % 4]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a + b, Point::new(-2, 7));
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(b * 2, Point::new(-6, 10));
+        assert_eq!(-a, Point::new(-1, -2));
+        assert_eq!((a - b).signum(), Point::new(1, -1));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(8, 7);
+        let b = Point::new(2, 10);
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 6);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(
+            Point::ORIGIN.neighbours4().collect::<Vec<Point>>(),
+            vec![
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ]
+        );
+        assert_eq!(Point::new(5, 5).neighbours8().count(), 8);
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Down.reverse(), Direction::Up);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.vector() + d.reverse().vector(), Point::ORIGIN);
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Point::from((3usize, 4usize)), Point::new(3, 4));
+        assert_eq!(<(usize, usize)>::try_from(Point::new(3, 4)), Ok((3, 4)));
+        assert!(<(usize, usize)>::try_from(Point::new(-1, 4)).is_err());
+    }
+}
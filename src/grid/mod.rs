@@ -0,0 +1,312 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{geometry::Point, parse::ParseError};
+
+/// A rectangular 2D grid stored row by row, indexed by `(x, y)` with `(0, 0)` at the top left
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parses a character map, padding short lines with `fill`
+    pub fn parse_padded(
+        day: u8,
+        text: &str,
+        fill: T,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let width = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut rows = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let mut row = parse_row(day, i, line, &mut f)?;
+            row.resize(width, fill.clone());
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    /// The `width` by `height` rectangle with its top left at `(x, y)`, clipped to the grid
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Grid<T> {
+        let (x, y) = (x.min(self.width), y.min(self.height));
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        Grid {
+            width,
+            height,
+            cells: (y..y + height)
+                .flat_map(|row| self.row(row)[x..x + width].iter().cloned())
+                .collect(),
+        }
+    }
+
+    pub fn transpose(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        }
+    }
+}
+
+fn parse_row<T>(
+    day: u8,
+    index: usize,
+    line: &str,
+    f: &mut impl FnMut(char) -> Option<T>,
+) -> Result<Vec<T>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(x, c)| {
+            f(c).ok_or_else(|| {
+                ParseError::new(day, index, line, format!("unexpected '{}'", c)).at_column(x)
+            })
+        })
+        .collect()
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from rows, or `None` if they aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map(|r| r.len()).unwrap_or(0);
+        if rows.iter().any(|r| r.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a character map where every line must be the same width
+    pub fn parse(
+        day: u8,
+        text: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let row = parse_row(day, i, line, &mut f)?;
+            if rows.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(
+                    ParseError::new(day, i, line, "rows must all be the same width")
+                        .at_column(row.len().min(rows[0].len())),
+                );
+            }
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Like `get`, but for points that may lie off the grid in any direction
+    pub fn at(&self, point: Point) -> Option<&T> {
+        let (x, y) = point.try_into().ok()?;
+        self.get(x, y)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        match self.contains(x, y) {
+            true => Some(&mut self.cells[y * self.width + x]),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The position of the first cell matching `predicate`, row by row
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, t)| predicate(t)).map(|(p, _)| p)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let neighbour = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+            self.contains(neighbour.0, neighbour.1).then_some(neighbour)
+        })
+    }
+
+    /// Orthogonal neighbours inside the grid, clockwise from up
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_4)
+    }
+
+    /// Orthogonal and diagonal neighbours inside the grid, clockwise from up-left
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_8)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid one character per cell, one line per row
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(self.contains(x, y), "({}, {}) is outside the grid", x, y);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(self.contains(x, y), "({}, {}) is outside the grid", x, y);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.at(point)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (x, y) = point
+            .try_into()
+            .unwrap_or_else(|_| panic!("{:?} is outside the grid", point));
+        &mut self[(x, y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_STR: &str = "abc
+def";
+
+    fn letters() -> Grid<char> {
+        Grid::parse(0, TEST_STR, Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = letters();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 'c');
+        assert_eq!(grid.get(0, 1), Some(&'d'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid[Point::new(1, 1)], 'e');
+        assert_eq!(grid.at(Point::new(-1, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Grid::parse(8, "12\n3x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = Grid::parse(8, "12\n3", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.message, "rows must all be the same width");
+    }
+
+    #[test]
+    fn test_parse_padded() {
+        let grid = Grid::parse_padded(0, "  #\n#", ' ', Some).unwrap();
+        assert_eq!(grid.render(|c| *c), "  #\n#  ");
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = letters();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.position(|c| *c == 'e'), Some((1, 1)));
+        assert_eq!(grid.transpose().render(|c| *c), "ad\nbe\ncf");
+        assert_eq!(grid.crop(1, 0, 5, 1).render(|c| *c), "bc");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = letters();
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4(1, 1).count(), 3);
+        assert_eq!(grid.neighbours8(1, 0).count(), 5);
+    }
+}
@@ -0,0 +1,50 @@
+//! Solutions to Advent of Code 2022.
+//!
+//! Each `dayN` module exposes its parser (`parse_file`), domain types and the
+//! helpers its parts are built from, plus a [`solution::Solution`] impl tying
+//! them together:
+//!
+//! ```
+//! use advent_of_code_2022::{day1::Day1, registry::Part, solution::{solve, Answer}};
+//!
+//! let timed = solve::<Day1>("1000\n2000\n\n2500", Part::One).unwrap();
+//! assert_eq!(timed.answer, Answer::Integer(3000));
+//! ```
+
+pub mod answers;
+pub mod bench;
+pub mod cancel;
+pub mod cli;
+pub mod client;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day2;
+pub mod day20;
+pub mod day22;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod examples;
+pub mod file;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod parse;
+pub mod pathfinding;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod trace;
+pub mod visualize;
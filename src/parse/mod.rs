@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+
+/// Where and why a puzzle input could not be parsed
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based line number, 0 when the problem is with the input as a whole
+    pub line: usize,
+    /// 1-based column
+    pub column: usize,
+    /// The offending line
+    pub text: String,
+    pub message: String,
+    /// Where the line passed to `new` starts in memory, so `at` can tell where a slice of it is
+    origin: usize,
+}
+
+impl PartialEq for ParseError {
+    fn eq(&self, other: &ParseError) -> bool {
+        (self.day, self.line, self.column, &self.text, &self.message)
+            == (
+                other.day,
+                other.line,
+                other.column,
+                &other.text,
+                &other.message,
+            )
+    }
+}
+
+impl Eq for ParseError {}
+
+impl ParseError {
+    /// An error on the line at `index` (0-based, as from `lines().enumerate()`)
+    pub fn new(day: u8, index: usize, line: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            day,
+            line: index + 1,
+            column: 1,
+            text: line.to_string(),
+            message: message.into(),
+            origin: line.as_ptr() as usize,
+        }
+    }
+
+    /// An error that can't be pinned to a single line, like a missing section
+    pub fn input(day: u8, message: impl Into<String>) -> ParseError {
+        ParseError {
+            day,
+            line: 0,
+            column: 0,
+            text: String::new(),
+            message: message.into(),
+            origin: 0,
+        }
+    }
+
+    /// Points the error at `token`, which should be a slice of the line passed to `new`.
+    /// Anything else is looked for in the line's text, and found at its first occurrence.
+    pub fn at(mut self, token: &str) -> ParseError {
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.origin);
+        let inside = offset
+            .checked_add(token.len())
+            .is_some_and(|end| end <= self.text.len());
+        let byte = match inside && self.text.is_char_boundary(offset) {
+            true => Some(offset),
+            false => self.text.find(token),
+        };
+        self.column = byte.map_or(1, |byte| self.text[..byte].chars().count() + 1);
+        self
+    }
+
+    /// Moves the error to the line at `index`, for parsers that only see one line
+    pub fn on_line(mut self, index: usize) -> ParseError {
+        self.line = index + 1;
+        self
+    }
+
+    /// Points the error at a 0-based character offset in the offending line
+    pub fn at_column(mut self, offset: usize) -> ParseError {
+        self.column = offset + 1;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "day {}: {}", self.day, self.message);
+        }
+        write!(
+            f,
+            "day {}, line {}, column {}: {}\n  {}\n  {}^",
+            self.day,
+            self.line,
+            self.column,
+            self.message,
+            self.text,
+            " ".repeat(self.column - 1)
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_token() {
+        let line = "A Q";
+        let error = ParseError::new(2, 2, line, "unknown play").at(&line[2..]);
+        assert_eq!(error.line, 3);
+        assert_eq!(error.column, 3);
+        assert_eq!(
+            error.to_string(),
+            "day 2, line 3, column 3: unknown play\n  A Q\n    ^"
+        );
+    }
+
+    #[test]
+    fn test_at_copied_token() {
+        let error = ParseError::new(4, 0, "2-4,6-x", "bad").at("x");
+        assert_eq!(error.column, 7);
+    }
+
+    #[test]
+    fn test_at_repeated_token() {
+        let line = "move 2 from 1 to 2";
+        let error = ParseError::new(5, 0, line, "no such stack").at(&line[17..]);
+        assert_eq!(error.column, 18);
+        let error = ParseError::new(5, 0, line, "no such stack").at(&line.trim()[5..6]);
+        assert_eq!(error.column, 6);
+    }
+
+    #[test]
+    fn test_at_after_non_ascii() {
+        let line = "é x";
+        assert_eq!(ParseError::new(1, 0, line, "bad").at(&line[3..]).column, 3);
+        assert_eq!(ParseError::new(1, 0, line, "bad").at("x").column, 3);
+    }
+
+    #[test]
+    fn test_input_error() {
+        let error = ParseError::input(12, "no start position");
+        assert_eq!(error.to_string(), "day 12: no start position");
+    }
+}
@@ -0,0 +1,306 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Shortest distances from one or more sources, with what's needed to rebuild each path
+#[derive(Debug, Clone)]
+pub struct Paths<N> {
+    distances: HashMap<N, usize>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Paths<N> {
+    fn new() -> Paths<N> {
+        Paths {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every reachable node and its distance
+    pub fn distances(&self) -> &HashMap<N, usize> {
+        &self.distances
+    }
+
+    /// The nodes from the nearest source to `node`, both ends included
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search outwards from every source at once, for unweighted graphs
+pub fn bfs<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if paths.distances.insert(source.clone(), 0).is_none() {
+            queue.push_back(source);
+        }
+    }
+    while let Some(current) = queue.pop_front() {
+        let distance = paths.distances[&current] + 1;
+        for next in successors(&current) {
+            if !paths.distances.contains_key(&next) {
+                paths.distances.insert(next.clone(), distance);
+                paths.parents.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// A node waiting in a priority queue, popped cheapest first
+struct Queued<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Dijkstra's algorithm from every source at once, for graphs with non-negative edge costs
+pub fn dijkstra<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+    for source in sources {
+        paths.distances.insert(source.clone(), 0);
+        queue.push(Queued {
+            priority: 0,
+            cost: 0,
+            node: source,
+        });
+    }
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if cost > paths.distances[&node] {
+            continue;
+        }
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            if paths.distance(&next).is_none_or(|d| next_cost < d) {
+                paths.distances.insert(next.clone(), next_cost);
+                paths.parents.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: next_cost,
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    paths
+}
+
+/// A* search for the cheapest path to a goal. `heuristic` must never overestimate the
+/// remaining cost, or the path found may not be the cheapest.
+pub fn astar<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+    goal: impl Fn(&N) -> bool,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+    paths.distances.insert(start.clone(), 0);
+    queue.push(Queued {
+        priority: heuristic(&start),
+        cost: 0,
+        node: start,
+    });
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if goal(&node) {
+            return Some((paths.path_to(&node).unwrap(), cost));
+        }
+        if cost > paths.distances[&node] {
+            continue;
+        }
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            if paths.distance(&next).is_none_or(|d| next_cost < d) {
+                paths.distances.insert(next.clone(), next_cost);
+                paths.parents.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Shortest distances between every pair of nodes
+#[derive(Debug, Clone)]
+pub struct AllPairs<N> {
+    index: HashMap<N, usize>,
+    distances: Vec<Vec<Option<usize>>>,
+}
+
+impl<N: Eq + Hash> AllPairs<N> {
+    pub fn distance(&self, from: &N, to: &N) -> Option<usize> {
+        self.distances[*self.index.get(from)?][*self.index.get(to)?]
+    }
+}
+
+/// Floyd–Warshall over `nodes`, ignoring edges to nodes not in the list
+pub fn floyd_warshall<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> AllPairs<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let nodes: Vec<N> = nodes.into_iter().collect();
+    let index: HashMap<N, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.clone(), i))
+        .collect();
+    let mut distances = vec![vec![None; nodes.len()]; nodes.len()];
+    for (i, node) in nodes.iter().enumerate() {
+        distances[i][i] = Some(0);
+        for (next, edge) in successors(node) {
+            if let Some(j) = index.get(&next) {
+                distances[i][*j] = Some(distances[i][*j].map_or(edge, |d: usize| d.min(edge)));
+            }
+        }
+    }
+    for k in 0..nodes.len() {
+        let through_k = distances[k].clone();
+        for row in distances.iter_mut() {
+            let Some(to_k) = row[k] else {
+                continue;
+            };
+            for (distance, from_k) in row.iter_mut().zip(through_k.iter()) {
+                if let Some(from_k) = from_k {
+                    if distance.is_none_or(|d| to_k + from_k < d) {
+                        *distance = Some(to_k + from_k);
+                    }
+                }
+            }
+        }
+    }
+    AllPairs { index, distances }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A --1-- B --1-- C
+    //  \             /
+    //   -----5------
+    fn edges(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'A' => vec![('B', 1), ('C', 5)],
+            'B' => vec![('A', 1), ('C', 1)],
+            'C' => vec![('B', 1), ('A', 5)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: &char) -> Vec<char> {
+        edges(node).into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let paths = bfs(['A'], unweighted);
+        assert_eq!(paths.distance(&'C'), Some(1));
+        assert_eq!(paths.path_to(&'C'), Some(vec!['A', 'C']));
+        assert_eq!(paths.path_to(&'A'), Some(vec!['A']));
+        assert_eq!(paths.distance(&'Z'), None);
+    }
+
+    #[test]
+    fn test_bfs_multi_source() {
+        let line = |n: &u32| vec![n.saturating_sub(1), (n + 1).min(10)];
+        let paths = bfs([0, 10], line);
+        assert_eq!(paths.distance(&5), Some(5));
+        assert_eq!(paths.distance(&8), Some(2));
+        assert_eq!(paths.path_to(&8), Some(vec![10, 9, 8]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let paths = dijkstra(['A'], edges);
+        assert_eq!(paths.distance(&'C'), Some(2));
+        assert_eq!(paths.path_to(&'C'), Some(vec!['A', 'B', 'C']));
+    }
+
+    #[test]
+    fn test_astar() {
+        // Walk a 10x10 open plane from corner to corner
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1))
+                .filter(|((x, y), _)| (0..10).contains(x) && (0..10).contains(y))
+        };
+        let heuristic = |(x, y): &(i32, i32)| (9 - x + 9 - y) as usize;
+        let (path, cost) = astar((0, 0), neighbours, heuristic, |p| *p == (9, 9)).unwrap();
+        assert_eq!(cost, 18);
+        assert_eq!(path.len(), 19);
+        assert_eq!(
+            astar((0, 0), neighbours, heuristic, |p| *p == (10, 10)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let all = floyd_warshall(['A', 'B', 'C'], edges);
+        assert_eq!(all.distance(&'A', &'C'), Some(2));
+        assert_eq!(all.distance(&'C', &'A'), Some(2));
+        assert_eq!(all.distance(&'B', &'B'), Some(0));
+        assert_eq!(all.distance(&'A', &'Z'), None);
+    }
+}
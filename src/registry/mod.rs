@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::{
+    file::{read_file, InputError, InputSource},
+    generate::Rng,
+    parse::ParseError,
+    solution::{solve, SolveError, Timed},
+    visualize::{visualize, Recorder},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            n => Err(n),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+pub type VisualizeFn = fn(&str, Part, &mut dyn Recorder) -> Result<(), ParseError>;
+
+/// Generates a random input of about `size` lines, items or steps
+pub type GenerateFn = fn(usize, &mut Rng) -> String;
+
+pub struct Day {
+    pub day: u8,
+    pub solve: fn(&str, Part) -> Result<Timed, SolveError>,
+    /// The checked-in input, compiled in with the `embed-inputs` feature
+    pub embedded: Option<&'static str>,
+    /// Replays the simulation frame by frame, for days that have one to watch
+    pub visualize: Option<VisualizeFn>,
+    /// Generates random inputs for stress testing
+    pub generate: Option<GenerateFn>,
+}
+
+impl Day {
+    pub fn module(&self) -> String {
+        format!("day{}", self.day)
+    }
+
+    pub fn input(&self, source: &InputSource) -> Result<String, InputError> {
+        read_file(&self.module(), source, self.embedded)
+    }
+}
+
+#[cfg(feature = "embed-inputs")]
+macro_rules! embedded_input {
+    ($module:ident) => {
+        Some(include_str!(concat!(
+            "../",
+            stringify!($module),
+            "/input.txt"
+        )))
+    };
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+macro_rules! embedded_input {
+    ($module:ident) => {
+        None
+    };
+}
+
+macro_rules! day {
+    ($day:literal, $module:ident :: $solution:ident) => {
+        Day {
+            day: $day,
+            solve: solve::<crate::$module::$solution>,
+            embedded: embedded_input!($module),
+            visualize: None,
+            generate: None,
+        }
+    };
+    ($day:literal, $module:ident :: $solution:ident, $($capability:ident),+) => {
+        Day {
+            $($capability: capability!($capability, $module::$solution),)+
+            ..day!($day, $module::$solution)
+        }
+    };
+}
+
+macro_rules! capability {
+    (visualize, $module:ident :: $solution:ident) => {
+        Some(visualize::<crate::$module::$solution>)
+    };
+    (generate, $module:ident :: $solution:ident) => {
+        Some(crate::$module::generate)
+    };
+}
+
+/// Every solved day, in order. Add new days here to make them runnable, or let
+/// `aoc new-day` do it.
+pub static DAYS: &[Day] = &[
+    day!(1, day1::Day1, generate),
+    day!(2, day2::Day2, generate),
+    day!(3, day3::Day3, generate),
+    day!(4, day4::Day4, generate),
+    day!(5, day5::Day5, generate),
+    day!(6, day6::Day6, generate),
+    day!(7, day7::Day7, generate),
+    day!(8, day8::Day8, generate),
+    day!(9, day9::Day9, visualize, generate),
+    day!(10, day10::Day10, visualize, generate),
+    day!(11, day11::Day11, generate),
+    day!(12, day12::Day12, generate),
+    day!(13, day13::Day13, generate),
+    day!(14, day14::Day14, visualize, generate),
+    day!(15, day15::Day15, generate),
+    day!(16, day16::Day16, generate),
+    day!(20, day20::Day20, generate),
+    day!(22, day22::Day22, visualize, generate),
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(12).unwrap().day, 12);
+        assert!(find(17).is_none());
+    }
+
+    #[test]
+    fn test_visualize() {
+        assert!(find(14).unwrap().visualize.is_some());
+        assert!(find(1).unwrap().visualize.is_none());
+    }
+
+    #[test]
+    fn test_days_are_sorted() {
+        assert!(DAYS.windows(2).all(|w| w[0].day < w[1].day));
+    }
+
+    #[test]
+    fn test_part_try_from() {
+        assert_eq!(Part::try_from(1), Ok(Part::One));
+        assert_eq!(Part::try_from(2), Ok(Part::Two));
+        assert_eq!(Part::try_from(3), Err(3));
+    }
+}
@@ -0,0 +1,213 @@
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+
+use crate::{
+    registry::Part,
+    runner::{self, Outcome, Status},
+    solution::Answer,
+};
+
+/// How answers are printed: an aligned table for people, or JSON or CSV for scripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            s => Err(s.to_string()),
+        }
+    }
+}
+
+/// The CSV header, and the keys of each JSON object
+pub const COLUMNS: [&str; 8] = [
+    "day",
+    "part",
+    "input",
+    "answer",
+    "type",
+    "duration_ns",
+    "status",
+    "error",
+];
+
+fn part_number(part: Part) -> u8 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+pub fn answer_type(answer: &Answer) -> &'static str {
+    match answer {
+        Answer::Integer(_) => "integer",
+        Answer::String(_) => "string",
+        Answer::Grid(_) => "grid",
+    }
+}
+
+/// The status as a single word, and its message if it has one
+fn status_fields(status: &Status) -> (&'static str, Option<&str>) {
+    match status {
+        Status::Ok => ("ok", None),
+        Status::Error(e) => ("error", Some(e)),
+        Status::Panicked(message) => ("panicked", Some(message)),
+        Status::TimedOut => ("timed_out", None),
+    }
+}
+
+fn outcome_json(o: &Outcome) -> Value {
+    let (status, error) = status_fields(&o.status);
+    json!({
+        "day": o.day,
+        "part": part_number(o.part),
+        "input": o.input,
+        "answer": match &o.answer {
+            Some(Answer::Integer(n)) => json!(n),
+            Some(Answer::String(s)) => json!(s),
+            Some(Answer::Grid(lines)) => json!(lines),
+            None => Value::Null,
+        },
+        "type": o.answer.as_ref().map(answer_type),
+        "duration_ns": o.duration.as_nanos() as u64,
+        "status": status,
+        "error": error,
+    })
+}
+
+pub fn to_json(outcomes: &[Outcome]) -> String {
+    let report = json!({
+        "results": outcomes.iter().map(outcome_json).collect::<Vec<Value>>(),
+    });
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+/// Quotes a CSV field if it contains anything that would break the row
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+pub fn to_csv(outcomes: &[Outcome]) -> String {
+    let mut lines = vec![COLUMNS.join(",")];
+    for o in outcomes {
+        let (status, error) = status_fields(&o.status);
+        let row = [
+            o.day.to_string(),
+            part_number(o.part).to_string(),
+            o.input.clone().unwrap_or_default(),
+            o.answer.as_ref().map(|a| a.to_string()).unwrap_or_default(),
+            o.answer
+                .as_ref()
+                .map(answer_type)
+                .unwrap_or_default()
+                .to_string(),
+            o.duration.as_nanos().to_string(),
+            status.to_string(),
+            error.unwrap_or_default().to_string(),
+        ];
+        lines.push(
+            row.iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Formats outcomes for printing, as a table by input set if any ran on one
+pub fn render(outcomes: &[Outcome], format: Format) -> String {
+    match format {
+        Format::Text if outcomes.iter().any(|o| o.input.is_some()) => {
+            runner::comparison_table(outcomes)
+        }
+        Format::Text => runner::summary_table(outcomes),
+        Format::Json => to_json(outcomes),
+        Format::Csv => to_csv(outcomes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn outcomes() -> Vec<Outcome> {
+        vec![
+            Outcome {
+                day: 5,
+                part: Part::One,
+                input: None,
+                answer: Some(Answer::String("CMZ".to_string())),
+                duration: Duration::from_micros(3),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 10,
+                part: Part::Two,
+                input: Some("alice".to_string()),
+                answer: Some(Answer::Grid(vec!["#.".to_string(), ".#".to_string()])),
+                duration: Duration::from_nanos(20),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 16,
+                part: Part::Two,
+                input: None,
+                answer: None,
+                duration: Duration::ZERO,
+                status: Status::Panicked("not implemented, yet".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("yaml".parse::<Format>(), Err("yaml".to_string()));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let report: Value = serde_json::from_str(&to_json(&outcomes())).unwrap();
+        let results = report["results"].as_array().unwrap();
+        assert_eq!(results[0]["answer"], "CMZ");
+        assert_eq!(results[0]["type"], "string");
+        assert_eq!(results[0]["duration_ns"], 3000);
+        assert_eq!(results[0]["input"], Value::Null);
+        assert_eq!(results[1]["answer"], json!(["#.", ".#"]));
+        assert_eq!(results[1]["input"], "alice");
+        assert_eq!(results[2]["status"], "panicked");
+        assert_eq!(results[2]["type"], Value::Null);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&outcomes());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "day,part,input,answer,type,duration_ns,status,error"
+        );
+        assert_eq!(lines[1], "5,1,,CMZ,string,3000,ok,");
+        // The grid's newline is quoted, so the row carries on onto the next line
+        assert_eq!(lines[2], "10,2,alice,\"#.");
+        assert_eq!(lines[3], ".#\",grid,20,ok,");
+        assert_eq!(lines[4], "16,2,,,,0,panicked,\"not implemented, yet\"");
+    }
+}
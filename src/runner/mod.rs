@@ -0,0 +1,443 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::{
+    cancel::{self, Token},
+    file::InputSource,
+    registry::{Day, Part},
+    solution::Answer,
+    trace,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Ok,
+    /// The input could not be read or parsed, or the puzzle it describes has no answer
+    Error(String),
+    Panicked(String),
+    /// The part was cancelled or ran out of time before finishing
+    TimedOut,
+}
+
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        *self == Status::Ok
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    /// The named input set the part ran on, if any
+    pub input: Option<String>,
+    pub answer: Option<Answer>,
+    pub duration: Duration,
+    pub status: Status,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f`, catching any panic without printing it, since the caller reports it instead.
+/// Panics anywhere else are still printed as usual.
+fn catch_quietly<T>(f: impl FnOnce() -> T) -> std::thread::Result<T> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                previous(info)
+            }
+        }));
+    });
+    let quiet = QUIET.with(|quiet| quiet.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|q| q.set(quiet));
+    result
+}
+
+/// Runs a single part, turning input errors, panics and cancellation into a failed status
+pub fn run_part(day: &Day, part: Part, source: &InputSource, token: &Token) -> Outcome {
+    let mut outcome = Outcome {
+        day: day.day,
+        part,
+        input: match source {
+            InputSource::Named(name) => Some(name.clone()),
+            _ => None,
+        },
+        answer: None,
+        duration: Duration::ZERO,
+        status: Status::Ok,
+    };
+    let text = match day.input(source) {
+        Ok(text) => text,
+        Err(e) => {
+            outcome.status = Status::Error(e.to_string());
+            return outcome;
+        }
+    };
+    let solve = || {
+        cancel::run(token, || {
+            trace::for_day(day.day, || (day.solve)(&text, part))
+        })
+    };
+    match catch_quietly(solve) {
+        Ok(Ok(Ok(timed))) => {
+            outcome.duration = timed.parse + timed.solve;
+            outcome.answer = Some(timed.answer);
+        }
+        Ok(Ok(Err(e))) => outcome.status = Status::Error(e.to_string()),
+        Ok(Err(_)) => outcome.status = Status::TimedOut,
+        Err(payload) => outcome.status = Status::Panicked(panic_message(payload)),
+    }
+    outcome
+}
+
+/// Runs every part of every day in parallel, keeping the results in day order. Each part
+/// gets its own `timeout`, starting when it does.
+pub fn run_parallel(
+    days: &[&Day],
+    parts: &[Part],
+    source: &InputSource,
+    token: &Token,
+    timeout: Option<Duration>,
+) -> Vec<Outcome> {
+    let jobs: Vec<(&Day, Part)> = days
+        .iter()
+        .flat_map(|day| parts.iter().map(move |part| (*day, *part)))
+        .collect();
+    jobs.into_par_iter()
+        .map(|(day, part)| run_part(day, part, source, &job_token(token, timeout)))
+        .collect()
+}
+
+/// Runs the parts of one day against each named input set in parallel, keeping the results
+/// in set order
+pub fn run_input_sets(
+    day: &Day,
+    parts: &[Part],
+    sets: &[String],
+    token: &Token,
+    timeout: Option<Duration>,
+) -> Vec<Outcome> {
+    let jobs: Vec<(&String, Part)> = sets
+        .iter()
+        .flat_map(|set| parts.iter().map(move |part| (set, *part)))
+        .collect();
+    jobs.into_par_iter()
+        .map(|(set, part)| {
+            let source = InputSource::Named(set.clone());
+            run_part(day, part, &source, &job_token(token, timeout))
+        })
+        .collect()
+}
+
+/// A token for one job, with its own `timeout` starting now
+fn job_token(token: &Token, timeout: Option<Duration>) -> Token {
+    match timeout {
+        Some(timeout) => token.clone().with_timeout(timeout),
+        None => token.clone(),
+    }
+}
+
+fn answer_cell(answer: &Option<Answer>) -> String {
+    match answer {
+        Some(Answer::Grid(lines)) => format!("({} line grid, below)", lines.len()),
+        Some(answer) => answer.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn status_cell(status: &Status) -> String {
+    match status {
+        Status::Ok => "ok".to_string(),
+        // Parse errors carry the offending line below the message; the table only has room for one
+        Status::Error(e) => format!("error: {}", e.lines().next().unwrap_or_default()),
+        Status::Panicked(message) => format!("panicked: {}", message),
+        Status::TimedOut => "timed out".to_string(),
+    }
+}
+
+/// The part, answer, time and status cells shared by every table of outcomes
+fn outcome_cells(o: &Outcome) -> [String; 4] {
+    [
+        o.part.to_string(),
+        answer_cell(&o.answer),
+        format!("{:.2?}", o.duration),
+        status_cell(&o.status),
+    ]
+}
+
+/// Aligns a table of `label` then outcome columns, labels right aligned unless `left_label`
+fn align(label: &str, left_label: bool, rows: Vec<(String, [String; 4])>) -> Vec<String> {
+    let mut widths = [label.len(), 4, 6, 4];
+    for (label, cells) in rows.iter() {
+        widths[0] = widths[0].max(label.chars().count());
+        for (width, cell) in widths[1..].iter_mut().zip(cells.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |label: &str, cells: &[String; 4]| {
+        let label = match left_label {
+            true => format!("{:<w$}", label, w = widths[0]),
+            false => format!("{:>w$}", label, w = widths[0]),
+        };
+        format!(
+            "{}  {:>w1$}  {:<w2$}  {:>w3$}  {}",
+            label,
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )
+        .trim_end()
+        .to_string()
+    };
+    let header = ["Part", "Answer", "Time", "Status"].map(|h| h.to_string());
+    let mut lines = vec![format_row(label, &header)];
+    lines.extend(rows.iter().map(|(label, cells)| format_row(label, cells)));
+    lines
+}
+
+/// An aligned table of outcomes, followed by any multi-line answers
+pub fn summary_table(outcomes: &[Outcome]) -> String {
+    let rows = outcomes
+        .iter()
+        .map(|o| (o.day.to_string(), outcome_cells(o)))
+        .collect();
+    let mut lines = align("Day", false, rows);
+
+    for o in outcomes {
+        if let Some(Answer::Grid(grid)) = &o.answer {
+            lines.push(String::new());
+            lines.push(format!("Day {} part {}:", o.day, o.part));
+            lines.extend(grid.iter().cloned());
+        }
+    }
+    lines.join("\n")
+}
+
+/// An aligned table of one day's outcomes for each input set, followed by any multi-line
+/// answers
+pub fn comparison_table(outcomes: &[Outcome]) -> String {
+    let input = |o: &Outcome| o.input.clone().unwrap_or_else(|| "-".to_string());
+    let rows = outcomes
+        .iter()
+        .map(|o| (input(o), outcome_cells(o)))
+        .collect();
+    let mut lines = align("Input", true, rows);
+
+    for o in outcomes {
+        if let Some(Answer::Grid(grid)) = &o.answer {
+            lines.push(String::new());
+            lines.push(format!("{} part {}:", input(o), o.part));
+            lines.extend(grid.iter().cloned());
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::ParseError,
+        registry,
+        solution::{solve, Solution, SolveError},
+    };
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input<'a> = &'a str;
+
+        fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+            if text.is_empty() {
+                return Err(ParseError::input(99, "empty input"));
+            }
+            Ok(text)
+        }
+
+        fn part1(input: Self::Input<'_>) -> Result<Answer, SolveError> {
+            cancel::current().check().unwrap_or_else(|c| c.abort());
+            if input == "?" {
+                return Err(SolveError::unsolvable(99, "no answer"));
+            }
+            Ok(input.len().into())
+        }
+
+        fn part2(_input: Self::Input<'_>) -> Result<Answer, SolveError> {
+            panic!("broken part 2")
+        }
+    }
+
+    static BROKEN: Day = Day {
+        day: 99,
+        solve: solve::<Broken>,
+        embedded: Some("abc"),
+        visualize: None,
+        generate: None,
+    };
+
+    #[test]
+    fn test_run_part_isolates_panics() {
+        let source = InputSource::Search;
+        let outcome = run_part(&BROKEN, Part::One, &source, &Token::new());
+        assert_eq!(outcome.status, Status::Ok);
+        assert_eq!(outcome.answer, Some(Answer::Integer(3)));
+
+        let outcome = run_part(&BROKEN, Part::Two, &source, &Token::new());
+        assert_eq!(
+            outcome.status,
+            Status::Panicked("broken part 2".to_string())
+        );
+        assert_eq!(outcome.answer, None);
+        // Only panics inside a part are kept quiet
+        assert!(!QUIET.with(Cell::get));
+    }
+
+    #[test]
+    fn test_run_part_input_error() {
+        let source = InputSource::Path("does/not/exist.txt".into());
+        let outcome = run_part(
+            registry::find(1).unwrap(),
+            Part::One,
+            &source,
+            &Token::new(),
+        );
+        assert!(matches!(outcome.status, Status::Error(_)));
+    }
+
+    #[test]
+    fn test_run_part_parse_error() {
+        let empty = Day {
+            embedded: Some(""),
+            ..BROKEN
+        };
+        let outcome = run_part(&empty, Part::One, &InputSource::Search, &Token::new());
+        assert_eq!(
+            outcome.status,
+            Status::Error("day 99: empty input".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_part_unsolvable() {
+        let unsolvable = Day {
+            embedded: Some("?"),
+            ..BROKEN
+        };
+        let outcome = run_part(&unsolvable, Part::One, &InputSource::Search, &Token::new());
+        assert_eq!(
+            outcome.status,
+            Status::Error("day 99: no answer".to_string())
+        );
+        assert_eq!(outcome.answer, None);
+    }
+
+    #[test]
+    fn test_run_part_timed_out() {
+        let token = Token::new().with_timeout(Duration::ZERO);
+        let outcome = run_part(&BROKEN, Part::One, &InputSource::Search, &token);
+        assert_eq!(outcome.status, Status::TimedOut);
+        assert_eq!(outcome.answer, None);
+    }
+
+    #[test]
+    fn test_run_parallel_keeps_order() {
+        let days = vec![registry::find(2).unwrap(), registry::find(1).unwrap()];
+        let outcomes = run_parallel(&days, &Part::ALL, &InputSource::Search, &Token::new(), None);
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|o| (o.day, o.part))
+                .collect::<Vec<(u8, Part)>>(),
+            vec![
+                (2, Part::One),
+                (2, Part::Two),
+                (1, Part::One),
+                (1, Part::Two)
+            ]
+        );
+        assert!(outcomes.iter().all(|o| o.status.is_ok()));
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let outcomes = vec![
+            Outcome {
+                day: 1,
+                part: Part::One,
+                input: None,
+                answer: Some(Answer::Integer(70764)),
+                duration: Duration::from_millis(1),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 10,
+                part: Part::Two,
+                input: None,
+                answer: Some(Answer::Grid(vec!["#.".to_string()])),
+                duration: Duration::from_millis(2),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 16,
+                part: Part::Two,
+                input: None,
+                answer: None,
+                duration: Duration::ZERO,
+                status: Status::Panicked("not implemented".to_string()),
+            },
+        ];
+        let table = summary_table(&outcomes);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Day  Part  Answer                  Time  Status");
+        assert_eq!(lines[1], "  1     1  70764                 1.00ms  ok");
+        assert_eq!(
+            lines[3],
+            " 16     2  -                     0.00ns  panicked: not implemented"
+        );
+        assert_eq!(lines[5], "Day 10 part 2:");
+        assert_eq!(lines[6], "#.");
+    }
+
+    #[test]
+    fn test_comparison_table() {
+        let outcome = |input: &str, answer: i128| Outcome {
+            day: 1,
+            part: Part::One,
+            input: Some(input.to_string()),
+            answer: Some(Answer::Integer(answer)),
+            duration: Duration::from_millis(1),
+            status: Status::Ok,
+        };
+        let outcomes = vec![outcome("alice", 70764), outcome("bob", 5)];
+        let table = comparison_table(&outcomes);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Input  Part  Answer    Time  Status");
+        assert_eq!(lines[1], "alice     1  70764   1.00ms  ok");
+        assert_eq!(lines[2], "bob       1  5       1.00ms  ok");
+    }
+}
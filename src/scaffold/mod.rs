@@ -0,0 +1,282 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// Advent of Code only has days 1 to 25
+    InvalidDay(u8),
+    AlreadyExists(PathBuf),
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A source file didn't look the way scaffolding expects
+    Unrecognised {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => write!(f, "Day {} is not between 1 and 25", day),
+            ScaffoldError::AlreadyExists(path) => {
+                write!(f, "{} already exists", path.display())
+            }
+            ScaffoldError::Io { path, error } => {
+                write!(f, "Could not write {}: {}", path.display(), error)
+            }
+            ScaffoldError::Unrecognised { path, message } => {
+                write!(f, "Could not update {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+/// The skeleton of a new day's module
+pub fn module_source(day: u8) -> String {
+    format!(
+        r#"use crate::{{
+    parse::ParseError,
+    solution::{{Answer, Solution, SolveError}},
+}};
+
+pub fn parse_file(text: &str) -> Result<Vec<String>, ParseError> {{
+    Ok(text.lines().map(|line| line.to_string()).collect())
+}}
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input<'a> = Vec<String>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {{
+        parse_file(text)
+    }}
+
+    fn part1(_input: Self::Input<'_>) -> Result<Answer, SolveError> {{
+        unimplemented!("day {day} part 1")
+    }}
+
+    fn part2(_input: Self::Input<'_>) -> Result<Answer, SolveError> {{
+        unimplemented!("day {day} part 2")
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    static TEST_STR: &str = include_str!("example1.txt");
+
+    #[test]
+    fn test_parse() {{
+        let lines = parse_file(TEST_STR).unwrap();
+        assert_eq!(lines.len(), TEST_STR.lines().count());
+    }}
+}}
+"#
+    )
+}
+
+/// Placeholder answers for the example, commented out until they are filled in
+fn examples_source() -> String {
+    "# Fill in the example's answers once the puzzle is read, then uncomment them\n\
+     # [example1]\n\
+     # part1 = \n\
+     # part2 = \n"
+        .to_string()
+}
+
+fn unrecognised(path: &Path, message: &str) -> ScaffoldError {
+    ScaffoldError::Unrecognised {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    }
+}
+
+/// Adds `pub mod dayN;` to lib.rs, keeping the module list sorted
+pub fn add_module(lib: &str, day: u8) -> Option<String> {
+    let name = format!("day{}", day);
+    let mut lines: Vec<&str> = lib.lines().collect();
+    let modules: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, line.strip_prefix("pub mod ")?.strip_suffix(';')?)))
+        .collect();
+    if modules.iter().any(|(_, module)| *module == name) {
+        return None;
+    }
+    let index = match modules.iter().find(|(_, module)| *module > name.as_str()) {
+        Some((i, _)) => *i,
+        None => modules.last()?.0 + 1,
+    };
+    let declaration = format!("pub mod {};", name);
+    lines.insert(index, &declaration);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the registry's `DAYS`, keeping it in day order
+pub fn add_registration(registry: &str, day: u8) -> Option<String> {
+    let mut lines: Vec<&str> = registry.lines().collect();
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("pub static DAYS"))?;
+    let end = start + lines[start..].iter().position(|l| *l == "];")?;
+    let mut index = end;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+        let registered: u8 = line
+            .trim()
+            .strip_prefix("day!(")?
+            .split(',')
+            .next()?
+            .parse()
+            .ok()?;
+        if registered == day {
+            return None;
+        }
+        if registered > day {
+            index = i;
+            break;
+        }
+    }
+    let entry = format!("    day!({}, day{}::Day{}),", day, day, day);
+    lines.insert(index, &entry);
+    Some(lines.join("\n") + "\n")
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, contents).map_err(|error| ScaffoldError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|error| ScaffoldError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Creates `src/dayN` under the crate at `root` with a module skeleton and empty fixtures,
+/// then declares and registers it. Returns every file written.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+    let src = root.join("src");
+    let directory = src.join(format!("day{}", day));
+    if directory.exists() {
+        return Err(ScaffoldError::AlreadyExists(directory));
+    }
+
+    // Work out both edits before writing anything, so a failure leaves the tree untouched
+    let lib_path = src.join("lib.rs");
+    let lib = add_module(&read(&lib_path)?, day)
+        .ok_or_else(|| unrecognised(&lib_path, "no list of `pub mod` declarations"))?;
+    let registry_path = src.join("registry").join("mod.rs");
+    let registry = add_registration(&read(&registry_path)?, day)
+        .ok_or_else(|| unrecognised(&registry_path, "no `DAYS` list of `day!` entries"))?;
+
+    fs::create_dir_all(&directory).map_err(|error| ScaffoldError::Io {
+        path: directory.clone(),
+        error,
+    })?;
+    let files = [
+        (directory.join("mod.rs"), module_source(day)),
+        (directory.join("example1.txt"), String::new()),
+        (directory.join("examples.toml"), examples_source()),
+        (directory.join("input.txt"), String::new()),
+        (lib_path, lib),
+        (registry_path, registry),
+    ];
+    for (path, contents) in files.iter() {
+        write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    static LIB: &str = "//! Docs
+
+pub mod cli;
+pub mod day1;
+pub mod day16;
+pub mod day2;
+// pub mod day22;
+pub mod day20;
+pub mod day3;
+pub mod examples;
+";
+
+    static REGISTRY: &str = "pub static DAYS: &[Day] = &[
+    day!(1, day1::Day1),
+    day!(9, day9::Day9, visualize, generate),
+    day!(20, day20::Day20, generate),
+];
+
+pub fn find() {}
+";
+
+    #[test]
+    fn test_add_module() {
+        let lib = add_module(LIB, 17).unwrap();
+        assert!(lib.contains("pub mod day16;\npub mod day17;\npub mod day2;\n"));
+        let lib = add_module(LIB, 21).unwrap();
+        assert!(lib.contains("pub mod day20;\npub mod day21;\npub mod day3;\n"));
+        let lib = add_module(LIB, 9).unwrap();
+        assert!(lib.contains("pub mod day3;\npub mod day9;\npub mod examples;\n"));
+        assert_eq!(add_module(LIB, 16), None);
+        assert_eq!(add_module("", 16), None);
+    }
+
+    #[test]
+    fn test_add_registration() {
+        let registry = add_registration(REGISTRY, 10).unwrap();
+        assert!(registry.contains(
+            "generate),\n    day!(10, day10::Day10),\n    day!(20, day20::Day20, generate),\n"
+        ));
+        let registry = add_registration(REGISTRY, 25).unwrap();
+        assert!(registry.contains("    day!(25, day25::Day25),\n];\n"));
+        assert_eq!(add_registration(REGISTRY, 9), None);
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join("aoc_scaffold");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/registry")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/registry/mod.rs"), REGISTRY).unwrap();
+
+        let files = new_day(&root, 17).unwrap();
+        assert_eq!(files.len(), 6);
+        let module = fs::read_to_string(root.join("src/day17/mod.rs")).unwrap();
+        assert!(module.contains("pub struct Day17;"));
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day17;"));
+
+        assert!(matches!(
+            new_day(&root, 17),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            new_day(&root, 26),
+            Err(ScaffoldError::InvalidDay(26))
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::{parse::ParseError, registry::Part};
+
+/// The result of solving one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Wide enough to hold any `u64` or `i64` a solver returns without wrapping
+    Integer(i128),
+    String(String),
+    /// Multi-line output, such as letters drawn on a screen
+    Grid(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::String(s) => write!(f, "{}", s),
+            Answer::Grid(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(i128::try_from(n).unwrap())
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(u8, u32, u64, usize, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::String(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::String(s.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Self {
+        Answer::Grid(lines)
+    }
+}
+
+/// Why a part has no answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input parsed, but describes a puzzle with no answer
+    Unsolvable {
+        day: u8,
+        message: String,
+    },
+}
+
+impl SolveError {
+    pub fn unsolvable(day: u8, message: impl Into<String>) -> SolveError {
+        SolveError::Unsolvable {
+            day,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> Self {
+        SolveError::Parse(error)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Parse(error) => write!(f, "{}", error),
+            SolveError::Unsolvable { day, message } => write!(f, "day {}: {}", day, message),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// A day's puzzle: a parser shared by both parts, and a solver for each part.
+pub trait Solution {
+    type Input<'a>;
+
+    fn parse(text: &str) -> Result<Self::Input<'_>, ParseError>;
+    fn part1(input: Self::Input<'_>) -> Result<Answer, SolveError>;
+    fn part2(input: Self::Input<'_>) -> Result<Answer, SolveError>;
+}
+
+/// An answer along with how long parsing and solving took
+#[derive(Debug, Clone)]
+pub struct Timed {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+pub fn solve<S: Solution>(text: &str, part: Part) -> Result<Timed, SolveError> {
+    let start = Instant::now();
+    let input = S::parse(text)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = match part {
+        Part::One => S::part1(input),
+        Part::Two => S::part2(input),
+    }?;
+    Ok(Timed {
+        answer,
+        parse,
+        solve: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input<'a> = Vec<i64>;
+
+        fn parse(text: &str) -> Result<Self::Input<'_>, ParseError> {
+            text.lines()
+                .enumerate()
+                .map(|(i, l)| {
+                    l.parse()
+                        .map_err(|_| ParseError::new(0, i, l, "not a number"))
+                })
+                .collect()
+        }
+
+        fn part1(input: Self::Input<'_>) -> Result<Answer, SolveError> {
+            match input.is_empty() {
+                true => Err(SolveError::unsolvable(0, "nothing to sum")),
+                false => Ok(input.iter().sum::<i64>().into()),
+            }
+        }
+
+        fn part2(input: Self::Input<'_>) -> Result<Answer, SolveError> {
+            Ok(input
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<String>>()
+                .into())
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve::<Sum>("1\n2\n3", Part::One).unwrap().answer,
+            Answer::Integer(6)
+        );
+        assert_eq!(
+            solve::<Sum>("1\n2", Part::Two).unwrap().answer.to_string(),
+            "1\n2".to_string()
+        );
+    }
+
+    #[test]
+    fn test_answer_from_wide_integers() {
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(
+            Answer::from(usize::MAX),
+            Answer::Integer(usize::MAX as i128)
+        );
+        assert_eq!(Answer::from(i64::MIN).to_string(), "-9223372036854775808");
+    }
+
+    #[test]
+    fn test_solve_parse_error() {
+        let Err(SolveError::Parse(error)) = solve::<Sum>("1\nx", Part::One) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.text.as_str()), (2, "x"));
+    }
+
+    #[test]
+    fn test_solve_unsolvable() {
+        let error = solve::<Sum>("", Part::One).unwrap_err();
+        assert_eq!(error, SolveError::unsolvable(0, "nothing to sum"));
+        assert_eq!(error.to_string(), "day 0: nothing to sum");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(
+            Answer::Grid(vec!["#.".to_string(), ".#".to_string()]).to_string(),
+            "#.\n.#"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// How much a solver says about what it is doing, from nothing to every step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Level {
+    #[default]
+    Off,
+    /// A few lines per part, such as the size of the parsed input
+    Info,
+    /// Every step, such as each move and the state it leaves behind
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Level::Off),
+            "info" => Ok(Level::Info),
+            "trace" => Ok(Level::Trace),
+            s => Err(s.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Off => f.pad("off"),
+            Level::Info => f.pad("info"),
+            Level::Trace => f.pad("trace"),
+        }
+    }
+}
+
+/// Which level each day logs at, parsed from a list like `info,5=trace,16=off`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Filter {
+    default: Level,
+    days: HashMap<u8, Level>,
+}
+
+impl Filter {
+    /// Every day at `level`
+    pub fn all(level: Level) -> Filter {
+        Filter {
+            default: level,
+            days: HashMap::new(),
+        }
+    }
+
+    pub fn level(&self, day: u8) -> Level {
+        self.days.get(&day).copied().unwrap_or(self.default)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for item in s.split(',') {
+            match item.split_once('=') {
+                Some((day, level)) => {
+                    let day = day.parse().map_err(|_| item.to_string())?;
+                    filter.days.insert(day, level.parse()?);
+                }
+                None => filter.default = item.parse()?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// Unset until the CLI asks for something, which leaves every day off
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+/// Sets the filter used by `for_day`
+pub fn set_filter(filter: Filter) {
+    *FILTER.write().unwrap() = Some(filter);
+}
+
+struct Scope {
+    day: u8,
+    level: Level,
+    /// Lines kept instead of printed, for tests
+    captured: Option<Vec<String>>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Scope> = const {
+        RefCell::new(Scope {
+            day: 0,
+            level: Level::Off,
+            captured: None,
+        })
+    };
+}
+
+/// Puts the previous scope back when dropped, even if the solver panics
+struct Restore(Option<Scope>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+fn scoped<T>(scope: Scope, f: impl FnOnce() -> T) -> (T, Option<Vec<String>>) {
+    let restore = Restore(Some(CURRENT.with(|current| current.replace(scope))));
+    let value = f();
+    let captured = CURRENT.with(|current| current.borrow_mut().captured.take());
+    drop(restore);
+    (value, captured)
+}
+
+/// Runs `f` logging as `day` at whatever level the filter gives it
+pub fn for_day<T>(day: u8, f: impl FnOnce() -> T) -> T {
+    let level = FILTER
+        .read()
+        .unwrap()
+        .as_ref()
+        .map_or(Level::Off, |filter| filter.level(day));
+    with_level(day, level, f)
+}
+
+/// Runs `f` logging as `day` at `level`, to stderr
+pub fn with_level<T>(day: u8, level: Level, f: impl FnOnce() -> T) -> T {
+    let scope = Scope {
+        day,
+        level,
+        captured: None,
+    };
+    scoped(scope, f).0
+}
+
+/// Runs `f` logging as `day` at `level`, returning the lines it logged instead of printing them
+pub fn capture<T>(day: u8, level: Level, f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let scope = Scope {
+        day,
+        level,
+        captured: Some(Vec::new()),
+    };
+    let (value, captured) = scoped(scope, f);
+    (value, captured.unwrap_or_default())
+}
+
+/// Whether anything logged at `level` on this thread would be seen
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && CURRENT.with(|current| level <= current.borrow().level)
+}
+
+/// Logs one line. Use the `info!` and `trace!` macros, which skip formatting when disabled.
+pub fn log(level: Level, args: fmt::Arguments) {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let line = format!("day {} {}: {}", current.day, level, args);
+        match current.captured.as_mut() {
+            Some(captured) => captured.push(line),
+            None => eprintln!("{}", line),
+        }
+    })
+}
+
+/// Logs a line at `Level::Info` if the current day is tracing at that level
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled($crate::trace::Level::Info) {
+            $crate::trace::log($crate::trace::Level::Info, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a line at `Level::Trace` if the current day is tracing every step
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled($crate::trace::Level::Trace) {
+            $crate::trace::log($crate::trace::Level::Trace, format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_from_str() {
+        let filter: Filter = "info,5=trace,16=off".parse().unwrap();
+        assert_eq!(filter.level(1), Level::Info);
+        assert_eq!(filter.level(5), Level::Trace);
+        assert_eq!(filter.level(16), Level::Off);
+        assert_eq!("7=trace".parse::<Filter>().unwrap().level(1), Level::Off);
+        assert_eq!("loud".parse::<Filter>(), Err("loud".to_string()));
+        assert_eq!("x=info".parse::<Filter>(), Err("x=info".to_string()));
+    }
+
+    #[test]
+    fn test_capture() {
+        let ((), lines) = capture(5, Level::Info, || {
+            crate::info!("{} stacks", 3);
+            crate::trace!("hidden");
+        });
+        assert_eq!(lines, vec!["day 5 info: 3 stacks"]);
+        // Outside of a scope nothing is enabled
+        assert!(!enabled(Level::Info));
+    }
+}
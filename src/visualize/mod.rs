@@ -0,0 +1,204 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::{grid::Grid, parse::ParseError, registry::Part, solution::Solution};
+
+/// One step of a simulation, drawn one character per cell
+pub type Frame = Grid<char>;
+
+/// Somewhere for a simulation to send its frames as it runs
+pub trait Recorder {
+    /// Whether anyone is watching, so simulations can skip drawing frames nobody will see
+    fn is_recording(&self) -> bool {
+        true
+    }
+
+    fn record(&mut self, frame: &Frame);
+
+    /// Flushes any output, reporting the first error seen while recording
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Records nothing, for running a simulation without watching it
+impl Recorder for () {
+    fn is_recording(&self) -> bool {
+        false
+    }
+
+    fn record(&mut self, _frame: &Frame) {}
+}
+
+/// Keeps every frame in memory
+#[derive(Debug, Default)]
+pub struct Frames(pub Vec<Frame>);
+
+impl Recorder for Frames {
+    fn record(&mut self, frame: &Frame) {
+        self.0.push(frame.clone());
+    }
+}
+
+/// Animates frames in place on a terminal
+pub struct Terminal<W: Write> {
+    out: W,
+    delay: Duration,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, delay: Duration) -> Terminal<W> {
+        Terminal {
+            out,
+            delay,
+            error: None,
+        }
+    }
+}
+
+impl<W: Write> Recorder for Terminal<W> {
+    fn is_recording(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn record(&mut self, frame: &Frame) {
+        // Move to the top left and clear the screen before each frame
+        let result = writeln!(self.out, "\x1b[H\x1b[2J{}", frame.render(|c| *c))
+            .and_then(|_| self.out.flush());
+        match result {
+            Ok(()) => thread::sleep(self.delay),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.error.take().map_or(Ok(()), Err)
+    }
+}
+
+/// Writes each frame to `directory` as a numbered PPM image, `scale` pixels per cell
+pub struct Ppm {
+    directory: PathBuf,
+    scale: usize,
+    count: usize,
+    error: Option<io::Error>,
+}
+
+impl Ppm {
+    pub fn new(directory: PathBuf, scale: usize) -> Ppm {
+        Ppm {
+            directory,
+            scale,
+            count: 0,
+            error: None,
+        }
+    }
+
+    fn write_frame(&self, frame: &Frame) -> io::Result<()> {
+        if self.count == 0 {
+            fs::create_dir_all(&self.directory)?;
+        }
+        let mut image = Vec::new();
+        write_ppm(&mut image, frame, self.scale)?;
+        fs::write(
+            self.directory.join(format!("frame{:06}.ppm", self.count)),
+            image,
+        )
+    }
+}
+
+impl Recorder for Ppm {
+    fn is_recording(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn record(&mut self, frame: &Frame) {
+        match self.write_frame(frame) {
+            Ok(()) => self.count += 1,
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.error.take().map_or(Ok(()), Err)
+    }
+}
+
+/// The colour each character is drawn in
+fn colour(c: char) -> [u8; 3] {
+    match c {
+        ' ' => [0, 0, 0],
+        '.' => [32, 32, 48],
+        '#' => [200, 200, 200],
+        'o' => [230, 190, 60],
+        '+' => [90, 160, 230],
+        _ => [220, 50, 50],
+    }
+}
+
+/// Encodes a frame as a binary PPM image
+pub fn write_ppm(out: &mut impl Write, frame: &Frame, scale: usize) -> io::Result<()> {
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        frame.width() * scale,
+        frame.height() * scale
+    )?;
+    for row in frame.rows() {
+        let line: Vec<u8> = row.iter().flat_map(|c| colour(*c).repeat(scale)).collect();
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
+
+/// A puzzle whose simulation can be watched step by step
+pub trait Visualize: Solution {
+    fn visualize(input: Self::Input<'_>, part: Part, recorder: &mut dyn Recorder);
+}
+
+pub fn visualize<S: Visualize>(
+    text: &str,
+    part: Part,
+    recorder: &mut dyn Recorder,
+) -> Result<(), ParseError> {
+    S::visualize(S::parse(text)?, part, recorder);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> Frame {
+        Grid::parse(0, "#.\n o", Some).unwrap()
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut image = Vec::new();
+        write_ppm(&mut image, &frame(), 2).unwrap();
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 4 * 4 * 3);
+        // The top left cell is scaled up to a 2x2 block
+        let pixels = &image[header.len()..];
+        assert_eq!(&pixels[0..6], &[200; 6]);
+        assert_eq!(&pixels[12..18], &[200; 6]);
+        assert_eq!(&pixels[30..33], &[230, 190, 60]);
+    }
+
+    #[test]
+    fn test_terminal() {
+        let mut out = Vec::new();
+        let mut terminal = Terminal::new(&mut out, Duration::ZERO);
+        terminal.record(&frame());
+        terminal.finish().unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[H\x1b[2J#.\n o\n");
+    }
+}
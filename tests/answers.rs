@@ -0,0 +1,19 @@
+use std::process::Command;
+
+/// Runs every registered solution against its checked-in input and
+/// compares it with the answers recorded in answers.toml.
+#[test]
+fn test_recorded_answers() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("verify")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,20 @@
+use advent_of_code_2022::{day12, day13, day4, registry, solution::Answer};
+
+#[test]
+fn day_helpers_are_public() {
+    let pairs = day4::parse_file("2-4,6-8\n2-8,3-7").unwrap();
+    assert_eq!(day4::get_redundant_elfs(&pairs).len(), 1);
+
+    let (grid, start, end) =
+        day12::parse_file("Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi").unwrap();
+    assert_eq!(day12::get_shortest_walk(&grid, start, end), Some(31));
+
+    assert!(day13::parse_line("[1,[2,3]]").unwrap() < day13::parse_line("[1,[4]]").unwrap());
+}
+
+#[test]
+fn registry_solves_days() {
+    let day = registry::find(6).unwrap();
+    let timed = (day.solve)("mjqjpqmgbljsphdztnvjfqwrcgsmlb", registry::Part::One).unwrap();
+    assert_eq!(timed.answer, Answer::Integer(7));
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent_of_code_2022::{
+    client::{
+        mock::{MockServer, Puzzles},
+        Client, ClientError, Verdict,
+    },
+    registry::Part,
+};
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn puzzles() -> Puzzles {
+    Puzzles::new()
+        .with_input(1, "1000\n2000")
+        .with_answer(1, Part::One, "3000")
+}
+
+/// Fetches an input and submits answers to the mock server, checking that inputs are cached
+/// and that no answer is ever sent twice.
+#[test]
+fn test_fetch_and_submit() {
+    let server = MockServer::start(0, "secret", puzzles()).unwrap();
+    let dir = cache_dir("aoc_client_flow");
+    let client = Client::new(&server.url(), "secret")
+        .with_cache_dir(dir.clone())
+        .with_throttle(Duration::ZERO);
+
+    assert_eq!(client.fetch_input(1).unwrap(), "1000\n2000\n");
+    assert_eq!(client.fetch_input(1).unwrap(), "1000\n2000\n");
+    assert_eq!(server.requests(), 1);
+
+    let too_high = Verdict::Wrong {
+        hint: Some("too high".to_string()),
+    };
+    assert_eq!(client.submit(1, Part::One, "5000").unwrap(), too_high);
+    assert_eq!(client.submit(1, Part::One, "5000").unwrap(), too_high);
+    assert_eq!(server.requests(), 2);
+
+    assert_eq!(
+        client.submit(1, Part::One, "3000").unwrap(),
+        Verdict::Correct
+    );
+    assert_eq!(
+        client.submit(1, Part::One, "4000").unwrap(),
+        Verdict::AlreadySolved
+    );
+    assert_eq!(server.requests(), 3);
+    assert_eq!(client.submissions().unwrap().len(), 2);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cooldown_and_errors() {
+    let puzzles = puzzles().with_cooldown(Duration::from_secs(60));
+    let server = MockServer::start(0, "secret", puzzles).unwrap();
+    let dir = cache_dir("aoc_client_errors");
+    let client = Client::new(&server.url(), "secret")
+        .with_cache_dir(dir.clone())
+        .with_throttle(Duration::ZERO);
+
+    client.submit(1, Part::One, "1").unwrap();
+    assert!(matches!(
+        client.submit(1, Part::One, "2").unwrap(),
+        Verdict::TooSoon { wait: Some(_) }
+    ));
+    // Only the final verdict is recorded, so the second answer can be tried again later
+    assert_eq!(client.submissions().unwrap().len(), 1);
+
+    assert!(matches!(
+        client.fetch_input(2),
+        Err(ClientError::Status { code: 404, .. })
+    ));
+    let stranger = Client::new(&server.url(), "wrong")
+        .with_cache_dir(dir.clone())
+        .with_throttle(Duration::ZERO);
+    assert!(matches!(
+        stranger.fetch_input(1),
+        Err(ClientError::Status { code: 400, .. })
+    ));
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,38 @@
+use advent_of_code_2022::{
+    answers::Verdict,
+    examples::discover,
+    registry::{Part, DAYS},
+};
+
+/// Runs every registered solution over every example fixture next to it and
+/// compares the answers with those recorded in the day's examples.toml.
+#[test]
+fn test_examples() {
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for example in discover(day).unwrap() {
+            for part in Part::ALL {
+                if example.answers.get(day.day, part).is_none() {
+                    continue;
+                }
+                checked += 1;
+                let label = format!("day {} part {} {}", day.day, part, example.name);
+                match (day.solve)(&example.input, part) {
+                    Ok(timed) => match example.answers.check(day.day, part, &timed.answer) {
+                        Verdict::Correct => {}
+                        Verdict::Wrong { expected } => failures.push(format!(
+                            "{}: expected {}, got {}",
+                            label, expected, timed.answer
+                        )),
+                        Verdict::Unrecorded => unreachable!(),
+                    },
+                    Err(error) => failures.push(format!("{}: {}", label, error)),
+                }
+            }
+        }
+    }
+
+    assert!(checked > 0, "no example fixtures found");
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,154 @@
+use advent_of_code_2022::{
+    day15::{self, Day15},
+    generate::Rng,
+    registry::{Part, DAYS},
+    solution::Solution,
+};
+
+/// Days whose solvers are too slow to run on random inputs in a test
+const SLOW: &[u8] = &[15];
+
+/// How big an input each test generates. Not a divisor of 1000, so day 20's grove
+/// coordinates don't all land on its 0.
+const SIZE: usize = 45;
+
+/// What each day that isn't too slow answers for its input generated from seeds 0, 1 and 2
+const EXPECTED: &[(u8, [[&str; 2]; 3])] = &[
+    (
+        1,
+        [
+            ["412089", "1145671"],
+            ["343644", "1008118"],
+            ["314350", "913323"],
+        ],
+    ),
+    (2, [["229", "199"], ["221", "264"], ["232", "232"]]),
+    (3, [["1259", "411"], ["1066", "484"], ["1049", "419"]]),
+    (4, [["12", "28"], ["16", "28"], ["14", "26"]]),
+    (
+        5,
+        [
+            ["ADOKQGTPF", "AHLKQGRRW"],
+            ["DYELBBOFH", "EXOLOBDYO"],
+            ["ZCGYRQLLJ", "HRGYBLQBZ"],
+        ],
+    ),
+    (6, [["33", "43"], ["32", "45"], ["33", "45"]]),
+    (
+        7,
+        [
+            ["78355", "11768863"],
+            ["5239", "10015411"],
+            ["87333", "14916344"],
+        ],
+    ),
+    (8, [["482", "28500"], ["461", "123648"], ["457", "34320"]]),
+    (9, [["315", "203"], ["379", "243"], ["420", "248"]]),
+    (
+        10,
+        [
+            [
+                "240",
+                concat!(
+                    "##..##.........................##...#...\n",
+                    "###.....................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................",
+                ),
+            ],
+            [
+                "280",
+                concat!(
+                    "##.............##...........#.#....##...\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................",
+                ),
+            ],
+            [
+                "400",
+                concat!(
+                    "##.................#....................\n",
+                    "..##....................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................\n",
+                    "........................................",
+                ),
+            ],
+        ],
+    ),
+    (
+        11,
+        [
+            ["191821", "202472550930"],
+            ["162004", "122841424776"],
+            ["55454", "22424460800"],
+        ],
+    ),
+    (12, [["65", "63"], ["65", "63"], ["65", "63"]]),
+    (13, [["517", "2646"], ["658", "2432"], ["450", "2485"]]),
+    (14, [["147", "18906"], ["348", "28019"], ["31", "20051"]]),
+    (16, [["2893", "3509"], ["2021", "2033"], ["2618", "2877"]]),
+    (
+        20,
+        [
+            ["5574", "2237551294821"],
+            ["9800", "-15907958987953"],
+            ["-951", "-11873549308390"],
+        ],
+    ),
+    (
+        22,
+        [["19111", "2044"], ["9041", "18106"], ["23081", "21102"]],
+    ),
+];
+
+/// Every generator is deterministic for a seed and produces input its day can parse and,
+/// unless it is too slow, solve to the expected answers.
+#[test]
+fn test_generators() {
+    for day in DAYS {
+        let Some(generate) = day.generate else {
+            continue;
+        };
+        let expected = EXPECTED.iter().find(|(d, _)| *d == day.day);
+        assert!(
+            expected.is_some() != SLOW.contains(&day.day),
+            "day {} needs expected answers, or to be marked as slow",
+            day.day
+        );
+        for seed in 0..3 {
+            let text = generate(SIZE, &mut Rng::new(seed));
+            assert_eq!(text, generate(SIZE, &mut Rng::new(seed)));
+            let Some((_, answers)) = expected else {
+                continue;
+            };
+            for (part, answer) in Part::ALL.iter().zip(answers[seed as usize]) {
+                match (day.solve)(&text, *part) {
+                    Ok(timed) => assert_eq!(
+                        timed.answer.to_string(),
+                        answer,
+                        "day {} seed {} part {}",
+                        day.day,
+                        seed,
+                        part
+                    ),
+                    Err(error) => panic!("day {} seed {}: {}", day.day, seed, error),
+                }
+            }
+        }
+    }
+}
+
+/// The slow days still parse what they generate
+#[test]
+fn test_slow_generators_parse() {
+    for seed in 0..3 {
+        assert!(Day15::parse(&day15::generate(SIZE, &mut Rng::new(seed))).is_ok());
+    }
+}